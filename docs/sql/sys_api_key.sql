@@ -0,0 +1,19 @@
+DROP TABLE IF EXISTS sys_api_key;
+create table sys_api_key
+(
+    id             bigint UNSIGNED auto_increment comment '主键'
+        primary key,
+    user_id        bigint UNSIGNED                    not null comment '用户ID',
+    key_name       varchar(50)                        not null comment '密钥名称',
+    key_prefix     varchar(10)                        not null comment '密钥前缀',
+    key_hash       char(64)                           not null comment '密钥(sha256)',
+    permissions    text                               not null comment '权限(api_url的json数组)',
+    expire_time    datetime                           null comment '过期时间',
+    last_used_time datetime                           null comment '最后使用时间',
+    create_time    datetime default CURRENT_TIMESTAMP not null comment '创建时间',
+    update_time    datetime default CURRENT_TIMESTAMP not null on update CURRENT_TIMESTAMP comment '修改时间',
+    -- 关联user表
+    foreign key (user_id) references sys_user(id) on delete cascade,
+    unique key idx_key_hash (key_hash)
+)
+    comment 'API密钥';
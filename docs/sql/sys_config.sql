@@ -0,0 +1,20 @@
+DROP TABLE IF EXISTS sys_config;
+create table sys_config
+(
+    id           int auto_increment comment '主键'
+        primary key,
+    config_name  varchar(100)                       not null comment '参数名称',
+    config_key   varchar(100)                       not null comment '参数键名',
+    config_value varchar(500)                       not null comment '参数值',
+    value_type   varchar(10)  default 'string'      not null comment '参数类型(string,number,bool,json), 运行时配置项由键名决定',
+    remark       varchar(255)                       null comment '备注',
+    create_time  datetime default CURRENT_TIMESTAMP not null comment '创建时间',
+    update_time  datetime default CURRENT_TIMESTAMP not null on update CURRENT_TIMESTAMP comment '修改时间',
+    constraint config_key
+        unique (config_key)
+)
+    comment '参数配置';
+
+-- 与application.yaml同名的键会覆盖文件中的配置
+INSERT INTO sys_config (config_name, config_key, config_value, value_type, remark) VALUES ('登录失败锁定次数', 'login_fail_retry', '3', 'number', '覆盖application.yaml');
+INSERT INTO sys_config (config_name, config_key, config_value, value_type, remark) VALUES ('访问令牌有效期(秒)', 'jwt_exp', '1800', 'number', '覆盖application.yaml');
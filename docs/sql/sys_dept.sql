@@ -0,0 +1,20 @@
+DROP TABLE IF EXISTS sys_dept;
+create table sys_dept
+(
+    id          int auto_increment comment '主键'
+        primary key,
+    parent_id   int          default 0                 not null comment '上级部门ID',
+    ancestors   varchar(255) default '0'               not null comment '祖级列表(逗号分隔)',
+    dept_name   varchar(50)                            not null comment '部门名称',
+    leader      varchar(50)                            null comment '负责人',
+    phone       varchar(20)                            null comment '联系电话',
+    status_id   tinyint      default 1                 not null comment '状态(1:正常，0:禁用)',
+    sort        int          default 1                 not null comment '排序',
+    remark      varchar(255)                           null comment '备注',
+    create_time datetime     default CURRENT_TIMESTAMP not null comment '创建时间',
+    update_time datetime     default CURRENT_TIMESTAMP not null on update CURRENT_TIMESTAMP comment '修改时间'
+)
+    comment '部门信息';
+
+-- 初始化数据
+INSERT INTO sys_dept (id, parent_id, ancestors, dept_name, status_id, sort, remark) VALUES (1, 0, '0', '总公司', 1, 1, '根部门');
@@ -0,0 +1,49 @@
+DROP TABLE IF EXISTS sys_dict_data;
+DROP TABLE IF EXISTS sys_dict_type;
+create table sys_dict_type
+(
+    id          int auto_increment comment '主键'
+        primary key,
+    dict_name   varchar(100)                       not null comment '字典名称',
+    dict_type   varchar(100)                       not null comment '字典类型编码',
+    status_id   tinyint  default 1                 not null comment '状态(1:正常，0:禁用)',
+    remark      varchar(255)                       null comment '备注',
+    create_time datetime default CURRENT_TIMESTAMP not null comment '创建时间',
+    update_time datetime default CURRENT_TIMESTAMP not null on update CURRENT_TIMESTAMP comment '修改时间',
+    constraint dict_type
+        unique (dict_type)
+)
+    comment '字典类型';
+
+create table sys_dict_data
+(
+    id          int auto_increment comment '主键'
+        primary key,
+    dict_type   varchar(100)                       not null comment '字典类型编码',
+    dict_label  varchar(100)                       not null comment '字典标签',
+    dict_value  varchar(100)                       not null comment '字典值',
+    list_class  varchar(100)                       null comment '标签样式',
+    status_id   tinyint  default 1                 not null comment '状态(1:正常，0:禁用)',
+    sort        int      default 1                 not null comment '排序',
+    remark      varchar(255)                       null comment '备注',
+    create_time datetime default CURRENT_TIMESTAMP not null comment '创建时间',
+    update_time datetime default CURRENT_TIMESTAMP not null on update CURRENT_TIMESTAMP comment '修改时间',
+    unique key idx_type_value (dict_type, dict_value)
+)
+    comment '字典数据';
+
+-- 初始化数据
+INSERT INTO sys_dict_type (id, dict_name, dict_type, status_id, remark) VALUES (1, '状态', 'sys_status', 1, 'status_id');
+INSERT INTO sys_dict_type (id, dict_name, dict_type, status_id, remark) VALUES (2, '菜单类型', 'sys_menu_type', 1, 'menu_type');
+INSERT INTO sys_dict_type (id, dict_name, dict_type, status_id, remark) VALUES (3, '数据范围', 'sys_data_scope', 1, '角色的data_scope');
+
+INSERT INTO sys_dict_data (dict_type, dict_label, dict_value, list_class, status_id, sort) VALUES ('sys_status', '正常', '1', 'success', 1, 1);
+INSERT INTO sys_dict_data (dict_type, dict_label, dict_value, list_class, status_id, sort) VALUES ('sys_status', '禁用', '0', 'danger', 1, 2);
+INSERT INTO sys_dict_data (dict_type, dict_label, dict_value, list_class, status_id, sort) VALUES ('sys_menu_type', '目录', '1', '', 1, 1);
+INSERT INTO sys_dict_data (dict_type, dict_label, dict_value, list_class, status_id, sort) VALUES ('sys_menu_type', '菜单', '2', '', 1, 2);
+INSERT INTO sys_dict_data (dict_type, dict_label, dict_value, list_class, status_id, sort) VALUES ('sys_menu_type', '按钮', '3', '', 1, 3);
+INSERT INTO sys_dict_data (dict_type, dict_label, dict_value, list_class, status_id, sort) VALUES ('sys_data_scope', '全部数据', '1', '', 1, 1);
+INSERT INTO sys_dict_data (dict_type, dict_label, dict_value, list_class, status_id, sort) VALUES ('sys_data_scope', '自定义部门', '2', '', 1, 2);
+INSERT INTO sys_dict_data (dict_type, dict_label, dict_value, list_class, status_id, sort) VALUES ('sys_data_scope', '本部门', '3', '', 1, 3);
+INSERT INTO sys_dict_data (dict_type, dict_label, dict_value, list_class, status_id, sort) VALUES ('sys_data_scope', '本部门及以下', '4', '', 1, 4);
+INSERT INTO sys_dict_data (dict_type, dict_label, dict_value, list_class, status_id, sort) VALUES ('sys_data_scope', '仅本人', '5', '', 1, 5);
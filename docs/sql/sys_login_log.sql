@@ -0,0 +1,18 @@
+DROP TABLE IF EXISTS sys_login_log;
+create table sys_login_log
+(
+    id          bigint UNSIGNED auto_increment comment '主键'
+        primary key,
+    user_id     bigint UNSIGNED                    null comment '用户ID(账号不存在时为空)',
+    account     varchar(50)                        not null comment '登录账号',
+    login_type  varchar(10)                        not null comment '登录方式(password,code,oidc,totp)',
+    status      tinyint                            not null comment '状态(0:失败，1:成功，2:等待两步验证)',
+    reason      varchar(64)                        not null default '' comment '结果代码(success,challenge或errors.yaml中的错误代码)',
+    msg         varchar(255)                       not null comment '失败原因',
+    ip          varchar(64)                        not null comment '客户端IP',
+    user_agent  varchar(500)                       not null comment 'User-Agent',
+    create_time datetime default CURRENT_TIMESTAMP not null comment '登录时间',
+    index idx_account_time (account, create_time),
+    index idx_create_time (create_time)
+)
+    comment '登录日志';
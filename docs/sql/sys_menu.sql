@@ -9,7 +9,7 @@ create table sys_menu
     sort        int          default 1                 not null comment '排序',
     parent_id   int                                 not null comment '父ID',
     menu_url    varchar(255) default ''                null comment '路由路径',
-    api_url     varchar(255) default ''                null comment '接口URL',
+    api_url     varchar(255) default ''                null comment '接口URL(可加请求方法前缀, 支持:param和*, 如 POST /api/user/:id)',
     menu_icon   varchar(255)                           null comment '菜单图标',
     remark      varchar(255)                           null comment '备注',
     create_time datetime     default CURRENT_TIMESTAMP not null comment '创建时间',
@@ -51,8 +51,37 @@ INSERT INTO sys_menu (id, menu_name, menu_type, status_id, sort, parent_id, menu
 INSERT INTO sys_menu (id, menu_name, menu_type, status_id, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (30, '删除菜单接口', 3, 1, 1, 5, '', '/api/menu_delete', '', '删除菜单接口');
 INSERT INTO sys_menu (id, menu_name, menu_type, status_id, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (31, '修改菜单弹窗', 3, 1, 1, 5, '', '/api/menu_update_view', '', '修改菜单弹窗');
 INSERT INTO sys_menu (id, menu_name, menu_type, status_id, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (32, '更新菜单接口', 3, 1, 1, 5, '', '/api/menu_update', '', '更新菜单接口');
+INSERT INTO sys_menu (id, menu_name, menu_type, status_id, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (33, '在线用户', 2, 1, 4, 2, '/online', '/api/online_user_list', '', '在线用户');
+INSERT INTO sys_menu (id, menu_name, menu_type, status_id, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (34, '用户会话列表', 3, 1, 1, 33, '', '/api/session_list', '', '用户会话列表接口');
+INSERT INTO sys_menu (id, menu_name, menu_type, status_id, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (35, '强制下线接口', 3, 1, 1, 33, '', '/api/session_kick', '', '强制会话下线接口');
+INSERT INTO sys_menu (id, menu_name, menu_type, status_id, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (36, '锁定用户列表', 3, 1, 1, 3, '', '/api/locked_user_list', '', '锁定用户列表接口');
+INSERT INTO sys_menu (id, menu_name, menu_type, status_id, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (37, '解锁用户接口', 3, 1, 1, 3, '', '/api/user_unlock', '', '解锁用户接口');
+INSERT INTO sys_menu (id, menu_name, menu_type, status_id, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (38, '部门管理', 2, 1, 5, 2, '/dept', '/api/dept_list', '', '部门管理');
+INSERT INTO sys_menu (id, menu_name, menu_type, status_id, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (39, '保存部门接口', 3, 1, 1, 38, '', '/api/dept_save', '', '保存部门接口');
+INSERT INTO sys_menu (id, menu_name, menu_type, status_id, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (40, '删除部门接口', 3, 1, 1, 38, '', '/api/dept_delete', '', '删除部门接口');
+INSERT INTO sys_menu (id, menu_name, menu_type, status_id, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (41, '更新部门接口', 3, 1, 1, 38, '', '/api/dept_update', '', '更新部门接口');
+INSERT INTO sys_menu (id, menu_name, menu_type, status_id, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (42, '角色数据范围', 3, 1, 1, 4, '', '/api/query_role_dept', '', '角色数据范围部门');
+INSERT INTO sys_menu (id, menu_name, menu_type, status_id, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (43, '保存角色数据范围', 3, 1, 1, 4, '', '/api/update_role_dept', '', '角色数据范围部门接口');
+INSERT INTO sys_menu (id, menu_name, menu_type, status_id, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (44, '岗位管理', 2, 1, 6, 2, '/post', '/api/post_list', '', '岗位管理');
+INSERT INTO sys_menu (id, menu_name, menu_type, status_id, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (45, '保存岗位接口', 3, 1, 1, 44, '', '/api/post_save', '', '保存岗位接口');
+INSERT INTO sys_menu (id, menu_name, menu_type, status_id, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (46, '删除岗位接口', 3, 1, 1, 44, '', '/api/post_delete', '', '删除岗位接口');
+INSERT INTO sys_menu (id, menu_name, menu_type, status_id, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (47, '更新岗位接口', 3, 1, 1, 44, '', '/api/post_update', '', '更新岗位接口');
+INSERT INTO sys_menu (id, menu_name, menu_type, status_id, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (48, '用户岗位关联', 3, 1, 1, 3, '', '/api/query_user_post', '', '用户岗位关联');
+INSERT INTO sys_menu (id, menu_name, menu_type, status_id, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (49, '保存用户岗位关联', 3, 1, 1, 3, '', '/api/update_user_post', '', '用户岗位关联接口');
+INSERT INTO sys_menu (id, menu_name, menu_type, status_id, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (50, '字典管理', 2, 1, 7, 2, '/dict', '/api/dict_type_list', '', '字典管理');
+INSERT INTO sys_menu (id, menu_name, menu_type, status_id, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (51, '保存字典类型接口', 3, 1, 1, 50, '', '/api/dict_type_save', '', '保存字典类型接口');
+INSERT INTO sys_menu (id, menu_name, menu_type, status_id, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (52, '删除字典类型接口', 3, 1, 1, 50, '', '/api/dict_type_delete', '', '删除字典类型接口');
+INSERT INTO sys_menu (id, menu_name, menu_type, status_id, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (53, '更新字典类型接口', 3, 1, 1, 50, '', '/api/dict_type_update', '', '更新字典类型接口');
+INSERT INTO sys_menu (id, menu_name, menu_type, status_id, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (54, '字典数据列表', 3, 1, 1, 50, '', '/api/dict_data_list', '', '字典数据列表接口');
+INSERT INTO sys_menu (id, menu_name, menu_type, status_id, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (55, '保存字典数据接口', 3, 1, 1, 50, '', '/api/dict_data_save', '', '保存字典数据接口');
+INSERT INTO sys_menu (id, menu_name, menu_type, status_id, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (56, '删除字典数据接口', 3, 1, 1, 50, '', '/api/dict_data_delete', '', '删除字典数据接口');
+INSERT INTO sys_menu (id, menu_name, menu_type, status_id, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (57, '更新字典数据接口', 3, 1, 1, 50, '', '/api/dict_data_update', '', '更新字典数据接口');
+INSERT INTO sys_menu (id, menu_name, menu_type, status_id, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (58, '参数设置', 2, 1, 8, 2, '/config', '/api/config_list', '', '参数设置');
+INSERT INTO sys_menu (id, menu_name, menu_type, status_id, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (59, '保存参数接口', 3, 1, 1, 58, '', '/api/config_save', '', '保存参数接口');
+INSERT INTO sys_menu (id, menu_name, menu_type, status_id, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (60, '删除参数接口', 3, 1, 1, 58, '', '/api/config_delete', '', '删除参数接口');
+INSERT INTO sys_menu (id, menu_name, menu_type, status_id, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (61, '更新参数接口', 3, 1, 1, 58, '', '/api/config_update', '', '更新参数接口');
 INSERT INTO sys_menu (id, menu_name, menu_type, status_id, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (63, '日志管理', 1, 1, 1, 0, '/log1', '', 'Setting', '');
-INSERT INTO sys_menu (id, menu_name, menu_type, status_id, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (64, '登录日志', 2, 1, 1, 63, '/log', '', 'Setting', '');
+INSERT INTO sys_menu (id, menu_name, menu_type, status_id, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (64, '登录日志', 2, 1, 1, 63, '/log', '/api/login_log_list', 'Setting', '登录日志');
 INSERT INTO sys_menu (id, menu_name, menu_type, status_id, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (65, '常用图表', 1, 1, 1, 0, '/line1', '', 'Setting', '');
 INSERT INTO sys_menu (id, menu_name, menu_type, status_id, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (66, '饼图', 2, 1, 1, 65, '/bar', '', 'Setting', '');
 INSERT INTO sys_menu (id, menu_name, menu_type, status_id, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (67, '线图', 2, 1, 1, 65, '/line', '', 'Setting', '');
@@ -60,3 +89,5 @@ INSERT INTO sys_menu (id, menu_name, menu_type, status_id, sort, parent_id, menu
 INSERT INTO sys_menu (id, menu_name, menu_type, status_id, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (69, '个人中心', 1, 1, 1, 0, '/center1', '', 'Setting', '');
 INSERT INTO sys_menu (id, menu_name, menu_type, status_id, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (72, '个人信息', 2, 1, 1, 69, '/center', '', 'Setting', '');
 INSERT INTO sys_menu (id, menu_name, menu_type, status_id, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (73, '个人设置', 2, 1, 1, 69, '/setting', '', 'Setting', '');
+INSERT INTO sys_menu (id, menu_name, menu_type, status_id, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (74, '操作日志', 2, 1, 2, 63, '/operlog', '/api/oper_log_list', 'Setting', '操作日志');
+INSERT INTO sys_menu (id, menu_name, menu_type, status_id, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (75, '导出操作日志', 3, 1, 1, 74, '', '/api/oper_log_export', '', '导出操作日志接口');
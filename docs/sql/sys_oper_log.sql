@@ -0,0 +1,19 @@
+DROP TABLE IF EXISTS sys_oper_log;
+create table sys_oper_log
+(
+    id          bigint UNSIGNED auto_increment comment '主键'
+        primary key,
+    user_id     bigint UNSIGNED                    null comment '用户ID(匿名接口为空)',
+    method      varchar(10)                        not null comment '请求方法',
+    path        varchar(255)                       not null comment '接口',
+    req_body    text                               null comment '请求参数(已脱敏)',
+    status      int                                not null comment 'http状态码',
+    code        int                                null comment '返回结果码',
+    ip          varchar(64)                        not null comment '客户端IP',
+    user_agent  varchar(500)                       not null comment 'User-Agent',
+    latency_ms  bigint UNSIGNED                    not null comment '耗时(毫秒)',
+    create_time datetime default CURRENT_TIMESTAMP not null comment '操作时间',
+    index idx_user_time (user_id, create_time),
+    index idx_create_time (create_time)
+)
+    comment '操作日志';
@@ -0,0 +1,13 @@
+DROP TABLE IF EXISTS sys_password_history;
+create table sys_password_history
+(
+    id          bigint UNSIGNED auto_increment comment '主键'
+        primary key,
+    user_id     bigint UNSIGNED                    not null comment '用户ID',
+    password    varchar(255) charset utf8mb3       not null comment '密码(hash)',
+    create_time datetime default CURRENT_TIMESTAMP not null comment '创建时间',
+    -- 关联user表
+    foreign key (user_id) references sys_user(id) on delete cascade,
+    index idx_user_id (user_id)
+)
+    comment '历史密码';
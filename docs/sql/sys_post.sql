@@ -0,0 +1,20 @@
+DROP TABLE IF EXISTS sys_post;
+create table sys_post
+(
+    id          int auto_increment comment '主键'
+        primary key,
+    post_code   varchar(64)                        not null comment '岗位编码',
+    post_name   varchar(50)                        not null comment '岗位名称',
+    status_id   tinyint  default 1                 not null comment '状态(1:正常，0:禁用)',
+    sort        int      default 1                 not null comment '排序',
+    remark      varchar(255)                       null comment '备注',
+    create_time datetime default CURRENT_TIMESTAMP not null comment '创建时间',
+    update_time datetime default CURRENT_TIMESTAMP not null on update CURRENT_TIMESTAMP comment '修改时间',
+    constraint post_code
+        unique (post_code)
+)
+    comment '岗位信息';
+
+INSERT INTO sys_post (id, post_code, post_name, status_id, sort, remark) VALUES (1, 'ceo', '董事长', 1, 1, '');
+INSERT INTO sys_post (id, post_code, post_name, status_id, sort, remark) VALUES (2, 'hr', '人力资源', 1, 2, '');
+INSERT INTO sys_post (id, post_code, post_name, status_id, sort, remark) VALUES (3, 'user', '普通员工', 1, 3, '');
@@ -7,6 +7,8 @@ create table sys_role
     status_id   tinyint  default 1                 not null comment '状态(1:正常，0:禁用)',
     sort        int      default 1                 not null comment '排序',
     remark      varchar(255)                       not null comment '备注',
+    require_2fa tinyint  default 0                 not null comment '要求两步验证(1:是，0:否)',
+    data_scope  tinyint  default 1                 not null comment '数据范围(1:全部,2:自定义部门,3:本部门,4:本部门及以下,5:仅本人)',
     create_time datetime default CURRENT_TIMESTAMP not null comment '创建时间',
     update_time datetime default CURRENT_TIMESTAMP not null on update CURRENT_TIMESTAMP comment '修改时间',
     constraint role_name
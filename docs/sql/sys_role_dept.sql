@@ -0,0 +1,18 @@
+DROP TABLE IF EXISTS sys_role_dept;
+create table sys_role_dept
+(
+    id          int auto_increment comment '主键'
+        primary key,
+    role_id     int                                not null comment '角色ID',
+    dept_id     int                                not null comment '部门ID',
+    create_time datetime default CURRENT_TIMESTAMP not null comment '创建时间',
+    update_time datetime default CURRENT_TIMESTAMP not null on update CURRENT_TIMESTAMP comment '修改时间',
+    -- 关联role表
+    foreign key (role_id) references sys_role(id)
+        on delete cascade,
+    -- 关联dept表
+    foreign key (dept_id) references sys_dept(id)
+        on delete cascade,
+    unique key idx_role_dept (role_id, dept_id)
+)
+    comment '角色数据范围部门关联表';
@@ -7,10 +7,19 @@ CREATE TABLE sys_user
         primary key,
     mobile      char(11) default ''                not null comment '手机',
     user_name   varchar(50)                        not null comment '姓名',
-    password    varchar(64) charset utf8mb3        null comment '密码',
+    password    varchar(255) charset utf8mb3       null comment '密码(argon2id, 旧的bcrypt/md5登录时升级)',
     status_id   tinyint  default 1                 not null comment '状态(1:正常，0:禁用)',
     sort        int      default 1                 not null comment '排序',
     remark      varchar(255)                       null comment '备注',
+    dept_id     int                                null comment '部门ID',
+    password_update_time datetime default CURRENT_TIMESTAMP null comment '密码修改时间',
+    auth_source varchar(10)   default 'local'      null comment '认证方式(local,ldap,oidc)',
+    locked_until datetime                          null comment '锁定截止时间',
+    lock_reason varchar(255)                       null comment '锁定原因',
+    totp_secret varchar(64)                        null comment '两步验证密钥',
+    totp_recovery text                             null comment '两步验证恢复码(hash)',
+    last_login_time datetime                       null comment '最后登录时间',
+    last_login_ip varchar(64)                      null comment '最后登录IP',
     create_time datetime default CURRENT_TIMESTAMP not null comment '创建时间',
     update_time datetime default CURRENT_TIMESTAMP not null on update CURRENT_TIMESTAMP comment '修改时间',
     constraint AK_phone
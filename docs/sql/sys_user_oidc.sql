@@ -0,0 +1,14 @@
+DROP TABLE IF EXISTS sys_user_oidc;
+create table sys_user_oidc
+(
+    id          bigint UNSIGNED auto_increment comment '主键'
+        primary key,
+    user_id     bigint UNSIGNED                    not null comment '用户ID',
+    issuer      varchar(255)                       not null comment '身份提供方',
+    subject     varchar(255)                       not null comment '身份提供方的用户标识(sub)',
+    create_time datetime default CURRENT_TIMESTAMP not null comment '创建时间',
+    -- 关联user表
+    foreign key (user_id) references sys_user(id) on delete cascade,
+    unique key idx_issuer_subject (issuer, subject)
+)
+    comment '单点登录账号关联';
@@ -0,0 +1,18 @@
+DROP TABLE IF EXISTS sys_user_post;
+create table sys_user_post
+(
+    id          int auto_increment comment '主键'
+        primary key,
+    user_id     bigint UNSIGNED                    not null comment '用户ID',
+    post_id     int                                not null comment '岗位ID',
+    create_time datetime default CURRENT_TIMESTAMP not null comment '创建时间',
+    update_time datetime default CURRENT_TIMESTAMP not null on update CURRENT_TIMESTAMP comment '修改时间',
+    -- 关联user表
+    foreign key (user_id) references sys_user(id)
+        on delete cascade,
+    -- 关联post表
+    foreign key (post_id) references sys_post(id)
+        on delete cascade,
+    unique key idx_user_post (user_id, post_id)
+)
+    comment '用户岗位关联表';
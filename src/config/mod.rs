@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 use std::env;
+use jsonwebtoken::Algorithm;
 use serde::Deserialize;
 use log::info;
 use crate::error::Result;
@@ -35,15 +36,32 @@ fn init_error() -> ErrorConfig {
 #[derive(Default, Debug, Deserialize)]
 pub struct Config {
     pub addr: String,
+    pub trust_proxy: bool,
     pub cache_type: String,
     pub jwt_secret: String,
     pub jwt_exp: u64,
     pub jwt_refresh_token: u64,
+    pub jwt_refresh_exp: u64,
+    pub jwt_keys: Vec<JwtKeyConfig>,
     pub white_list_api: Vec<String>,
+    pub auth_only_api: Vec<String>,
+    pub rate_limits: Vec<RateLimitConfig>,
+    pub oper_log: OperLogConfig,
     pub login_fail_retry: u64,
     pub login_fail_retry_wait_sec: u64,
+    pub login_lock_max_sec: u64,
+    pub login_captcha_retry: u64,
     pub trash_recycle_days: u64,
     pub datetime_format: String,
+    pub totp_issuer: String,
+    pub sms_sender: String,
+    pub sms_file: String,
+    pub sms_code_exp: u64,
+    pub sms_send_interval: u64,
+    pub password_policy: PasswordPolicy,
+    pub password_reset_exp: u64,
+    pub oidc: Option<OidcConfig>,
+    pub ldap: Option<LdapConfig>,
     // pub log: LogConfig,
     pub redis_url: String,
     pub db: DBConfig,
@@ -56,12 +74,12 @@ impl Config {
         init_config()
     }
 
-    pub fn get_error(&self, code: &str) -> Result<()> {
+    pub fn get_error<T>(&self, code: &str) -> Result<T> {
         let error_info = self.get_error_info(code);
         Err(Error::Code(code.to_string(), error_info))
     }
 
-    pub fn get_error_arg(&self, code: &str, arg: String) -> Result<()> {
+    pub fn get_error_arg<T>(&self, code: &str, arg: String) -> Result<T> {
         let error_info = self.get_error_info(code);
         Err(Error::Code(code.to_string(), error_info.replace("{}", &arg)))
     }
@@ -75,6 +93,112 @@ impl Config {
 }
 
 
+#[derive(Debug, Deserialize)]
+pub struct OidcConfig {
+    /// discovery is read from `{issuer}/.well-known/openid-configuration`
+    pub issuer: String,
+    pub client_id: String,
+    pub client_secret: String,
+    /// the frontend page receiving `code` and `state`
+    pub redirect_uri: String,
+    pub scopes: Vec<String>,
+    pub mobile_claim: String,
+    pub name_claim: String,
+    pub group_claim: String,
+    /// link a new subject to the user with the same mobile, only when `phone_number_verified` is true
+    #[serde(default)]
+    pub link_by_mobile: bool,
+    /// create the user on the first login
+    #[serde(default)]
+    pub jit_provision: bool,
+    /// replace the roles of the user with the mapped roles on every login,
+    /// provisioned users get them once either way
+    #[serde(default)]
+    pub sync_roles: bool,
+    #[serde(default)]
+    pub default_role_ids: Vec<i32>,
+    /// group -> role ids
+    #[serde(default)]
+    pub role_mapping: HashMap<String, Vec<i32>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LdapConfig {
+    /// ldap://host:389 or ldaps://host:636
+    pub url: String,
+    /// service account to search users, anonymous search when empty
+    #[serde(default)]
+    pub bind_dn: String,
+    #[serde(default)]
+    pub bind_password: String,
+    pub base_dn: String,
+    /// `{}` is replaced by the mobile of the login
+    pub user_filter: String,
+    pub name_attr: String,
+    pub group_attr: String,
+    /// create the user on the first login
+    #[serde(default)]
+    pub jit_provision: bool,
+    /// replace the roles of the user with the mapped roles on every login
+    #[serde(default)]
+    pub sync_roles: bool,
+    #[serde(default)]
+    pub default_role_ids: Vec<i32>,
+    /// group dn or cn -> role ids
+    #[serde(default)]
+    pub role_mapping: HashMap<String, Vec<i32>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RateLimitConfig {
+    /// api pattern, same syntax as `white_list_api`
+    pub path: String,
+    pub key: RateLimitKey,
+    /// burst size
+    pub capacity: u64,
+    /// tokens added per second
+    pub rate: f64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RateLimitKey {
+    /// client ip
+    Ip,
+    /// logged in user, the ip for anonymous api
+    User,
+    /// the request path, shared by all clients
+    Route,
+}
+
+#[derive(Default, Debug, Deserialize)]
+pub struct OperLogConfig {
+    pub enabled: bool,
+    /// api not logged, same syntax as `white_list_api`
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    /// json fields of the request body replaced by "******"
+    #[serde(default)]
+    pub redact_fields: Vec<String>,
+    /// the logged request body is cut to this many bytes
+    pub max_body: usize,
+    /// max rows of an export
+    pub export_limit: u64,
+}
+
+#[derive(Default, Debug, Clone, Deserialize)]
+pub struct PasswordPolicy {
+    pub min_length: usize,
+    pub require_upper: bool,
+    pub require_lower: bool,
+    pub require_digit: bool,
+    pub require_special: bool,
+    /// the last passwords that can't be used again, 0 = no history
+    pub history: u64,
+    /// days until the password must be changed, 0 = never expires
+    pub max_age_days: u64,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct LogConfig {
     pub level: String,
@@ -94,6 +218,15 @@ impl Default for LogConfig {
     }
 }
 
+/// asymmetric jwt key, pem files
+#[derive(Debug, Deserialize)]
+pub struct JwtKeyConfig {
+    pub kid: String,
+    pub alg: Algorithm,
+    pub private_key: Option<String>,
+    pub public_key: String,
+}
+
 #[derive(Debug,Default, Deserialize)]
 pub struct DBConfig{
     pub url: String,
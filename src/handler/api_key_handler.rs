@@ -0,0 +1,39 @@
+use axum::{Json, Router};
+use axum::response::IntoResponse;
+use axum::routing::post;
+use log::info;
+
+use crate::middleware::context::UserContext;
+use crate::service::api_key_service;
+use crate::vo::Response;
+use crate::vo::api_key_vo::*;
+
+pub fn router() -> Router {
+    Router::new()
+        .route("/api_key_list", post(api_key_list))
+        .route("/api_key_save", post(api_key_save))
+        .route("/api_key_delete", post(api_key_delete))
+}
+
+// 查询当前用户的API密钥
+pub async fn api_key_list(user: UserContext) -> impl IntoResponse {
+    let result = api_key_service::list(user.id).await;
+    let total = result.as_ref().map_or(0, |data| data.len() as u64);
+    Response::result_page(result, total)
+}
+
+// 创建API密钥, 密钥只在创建时返回一次
+pub async fn api_key_save(user: UserContext, Json(item): Json<ApiKeySaveReq>) -> impl IntoResponse {
+    info!("api_key_save params: {:?}", &item);
+
+    let result = api_key_service::save(user.id, item).await;
+    Response::result(result)
+}
+
+// 吊销API密钥
+pub async fn api_key_delete(user: UserContext, Json(item): Json<ApiKeyDeleteReq>) -> impl IntoResponse {
+    info!("api_key_delete params: {:?}", &item);
+
+    let result = api_key_service::delete(user.id, &item.ids).await;
+    Response::result(result)
+}
\ No newline at end of file
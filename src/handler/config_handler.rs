@@ -0,0 +1,51 @@
+use axum::{Json, Router};
+use axum::response::IntoResponse;
+use axum::routing::post;
+use log::info;
+
+use crate::service::config_service;
+use crate::vo::*;
+use crate::vo::config_vo::*;
+
+pub fn router() -> Router
+{
+    Router::new()
+        .route("/config_list", post(config_list))
+        .route("/config_save", post(config_save))
+        .route("/config_delete", post(config_delete))
+        .route("/config_update", post(config_update))
+}
+
+// 查询参数列表
+pub async fn config_list(Json(item): Json<ConfigListReq>) -> impl IntoResponse {
+    info!("config_list params: {:?}", &item);
+
+    let result = config_service::config_list(item).await;
+    let total = result.as_ref().map_or(0, |data| data.total);
+    let result = result.map(|data| data.records);
+    Response::result_page(result, total)
+}
+
+// 添加参数
+pub async fn config_save(Json(item): Json<ConfigSaveReq>) -> impl IntoResponse {
+    info!("config_save params: {:?}", &item);
+
+    let result = config_service::config_save(item).await;
+    Response::result(result)
+}
+
+// 更新参数
+pub async fn config_update(Json(item): Json<ConfigUpdateReq>) -> impl IntoResponse {
+    info!("config_update params: {:?}", &item);
+
+    let result = config_service::config_update(item).await;
+    Response::result(result)
+}
+
+// 删除参数
+pub async fn config_delete(Json(item): Json<ConfigDeleteReq>) -> impl IntoResponse {
+    info!("config_delete params: {:?}", &item);
+
+    let result = config_service::config_delete(item).await;
+    Response::result(result)
+}
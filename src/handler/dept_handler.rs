@@ -0,0 +1,49 @@
+use axum::{Json, Router};
+use axum::response::IntoResponse;
+use axum::routing::post;
+use log::info;
+
+use crate::service::dept_service;
+use crate::vo::Response;
+use crate::vo::dept_vo::*;
+
+pub fn router() -> Router {
+    Router::new()
+        .route("/dept_list", post(dept_list))
+        .route("/dept_save", post(dept_save))
+        .route("/dept_delete", post(dept_delete))
+        .route("/dept_update", post(dept_update))
+}
+
+// 查询部门
+pub async fn dept_list(Json(item): Json<DeptListReq>) -> impl IntoResponse {
+    info!("dept_list params: {:?}", &item);
+
+    let result = dept_service::dept_list(item).await;
+    Response::result_page(result, 0)
+
+}
+
+// 添加部门
+pub async fn dept_save(Json(item): Json<DeptSaveReq>) -> impl IntoResponse {
+    info!("dept_save params: {:?}", &item);
+
+    let result = dept_service::dept_save(item).await;
+    Response::result(result)
+}
+
+// 更新部门
+pub async fn dept_update(Json(item): Json<DeptUpdateReq>) -> impl IntoResponse {
+    info!("dept_update params: {:?}", &item);
+
+    let result = dept_service::dept_update(item).await;
+    Response::result(result)
+}
+
+// 删除部门信息
+pub async fn dept_delete(Json(item): Json<DeptDeleteReq>) -> impl IntoResponse {
+    info!("dept_delete params: {:?}", &item);
+
+    let result = dept_service::dept_delete(item).await;
+    Response::result(result)
+}
\ No newline at end of file
@@ -0,0 +1,96 @@
+use axum::{Json, Router};
+use axum::response::IntoResponse;
+use axum::routing::post;
+use log::info;
+
+use crate::service::dict_service;
+use crate::vo::*;
+use crate::vo::dict_vo::*;
+
+pub fn router() -> Router
+{
+    Router::new()
+        .route("/dict_type_list", post(dict_type_list))
+        .route("/dict_type_save", post(dict_type_save))
+        .route("/dict_type_delete", post(dict_type_delete))
+        .route("/dict_type_update", post(dict_type_update))
+        .route("/dict_data_list", post(dict_data_list))
+        .route("/dict_data_save", post(dict_data_save))
+        .route("/dict_data_delete", post(dict_data_delete))
+        .route("/dict_data_update", post(dict_data_update))
+        .route("/dict_lookup", post(dict_lookup))
+}
+
+// 查询字典类型列表
+pub async fn dict_type_list(Json(item): Json<DictTypeListReq>) -> impl IntoResponse {
+    info!("dict_type_list params: {:?}", &item);
+
+    let result = dict_service::dict_type_list(item).await;
+    let total = result.as_ref().map_or(0, |data| data.total);
+    let result = result.map(|data| data.records);
+    Response::result_page(result, total)
+}
+
+// 添加字典类型
+pub async fn dict_type_save(Json(item): Json<DictTypeSaveReq>) -> impl IntoResponse {
+    info!("dict_type_save params: {:?}", &item);
+
+    let result = dict_service::dict_type_save(item).await;
+    Response::result(result)
+}
+
+// 更新字典类型
+pub async fn dict_type_update(Json(item): Json<DictTypeUpdateReq>) -> impl IntoResponse {
+    info!("dict_type_update params: {:?}", &item);
+
+    let result = dict_service::dict_type_update(item).await;
+    Response::result(result)
+}
+
+// 删除字典类型
+pub async fn dict_type_delete(Json(item): Json<DictTypeDeleteReq>) -> impl IntoResponse {
+    info!("dict_type_delete params: {:?}", &item);
+
+    let result = dict_service::dict_type_delete(item).await;
+    Response::result(result)
+}
+
+// 查询字典数据列表
+pub async fn dict_data_list(Json(item): Json<DictDataListReq>) -> impl IntoResponse {
+    info!("dict_data_list params: {:?}", &item);
+
+    let result = dict_service::dict_data_list(item).await;
+    let total = result.as_ref().map_or(0, |data| data.total);
+    let result = result.map(|data| data.records);
+    Response::result_page(result, total)
+}
+
+// 添加字典数据
+pub async fn dict_data_save(Json(item): Json<DictDataSaveReq>) -> impl IntoResponse {
+    info!("dict_data_save params: {:?}", &item);
+
+    let result = dict_service::dict_data_save(item).await;
+    Response::result(result)
+}
+
+// 更新字典数据
+pub async fn dict_data_update(Json(item): Json<DictDataUpdateReq>) -> impl IntoResponse {
+    info!("dict_data_update params: {:?}", &item);
+
+    let result = dict_service::dict_data_update(item).await;
+    Response::result(result)
+}
+
+// 删除字典数据
+pub async fn dict_data_delete(Json(item): Json<DictDataDeleteReq>) -> impl IntoResponse {
+    info!("dict_data_delete params: {:?}", &item);
+
+    let result = dict_service::dict_data_delete(item).await;
+    Response::result(result)
+}
+
+// 按字典类型查询标签, 不需要登录
+pub async fn dict_lookup(Json(item): Json<DictLookupReq>) -> impl IntoResponse {
+    let result = dict_service::dict_lookup(item).await;
+    Response::result(result)
+}
@@ -0,0 +1,24 @@
+use axum::{Json, Router};
+use axum::response::IntoResponse;
+use axum::routing::post;
+use log::info;
+
+use crate::service::login_log_service;
+use crate::vo::*;
+use crate::vo::login_log_vo::*;
+
+pub fn router() -> Router
+{
+    Router::new()
+        .route("/login_log_list", post(login_log_list))
+}
+
+// 查询登录日志
+pub async fn login_log_list(Json(item): Json<LoginLogListReq>) -> impl IntoResponse {
+    info!("login_log_list params: {:?}", &item);
+
+    let result = login_log_service::login_log_list(item).await;
+    let total = result.as_ref().map_or(0, |data| data.total);
+    let result = result.map(|data| data.records);
+    Response::result_page(result, total)
+}
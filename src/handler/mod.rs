@@ -1,4 +1,13 @@
 pub mod root;
 pub mod user_handler;
 pub mod role_handler;
-pub mod menu_handler;
\ No newline at end of file
+pub mod menu_handler;
+pub mod session_handler;
+pub mod api_key_handler;
+pub mod oidc_handler;
+pub mod dept_handler;
+pub mod post_handler;
+pub mod dict_handler;
+pub mod config_handler;
+pub mod oper_log_handler;
+pub mod login_log_handler;
\ No newline at end of file
@@ -0,0 +1,29 @@
+use axum::{Json, Router};
+use axum::response::IntoResponse;
+use axum::routing::post;
+use log::info;
+
+use crate::middleware::context::ClientInfo;
+use crate::service::{oidc_service, user_service};
+use crate::vo::Response;
+use crate::vo::oidc_vo::*;
+
+pub fn router() -> Router {
+    Router::new()
+        .route("/oidc_authorize", post(oidc_authorize))
+        .route("/oidc_login", post(oidc_login))
+}
+
+// 单点登录, 获取身份提供方的授权地址
+pub async fn oidc_authorize() -> impl IntoResponse {
+    let result = oidc_service::authorize().await;
+    Response::result(result)
+}
+
+// 单点登录, 使用授权码登录
+pub async fn oidc_login(client: ClientInfo, Json(item): Json<OidcLoginReq>) -> impl IntoResponse {
+    info!("oidc_login state: {}, client: {:?}", &item.state, &client);
+
+    let result = user_service::login_oidc(item, client).await;
+    Response::result(result)
+}
\ No newline at end of file
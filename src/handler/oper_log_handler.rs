@@ -0,0 +1,41 @@
+use axum::body::Body;
+use axum::{Json, Router};
+use axum::http::header;
+use axum::response::IntoResponse;
+use axum::routing::post;
+use log::info;
+
+use crate::service::oper_log_service;
+use crate::vo::*;
+use crate::vo::oper_log_vo::*;
+
+pub fn router() -> Router
+{
+    Router::new()
+        .route("/oper_log_list", post(oper_log_list))
+        .route("/oper_log_export", post(oper_log_export))
+}
+
+// 查询操作日志
+pub async fn oper_log_list(Json(item): Json<OperLogListReq>) -> impl IntoResponse {
+    info!("oper_log_list params: {:?}", &item);
+
+    let result = oper_log_service::oper_log_list(item).await;
+    let total = result.as_ref().map_or(0, |data| data.total);
+    let result = result.map(|data| data.records);
+    Response::result_page(result, total)
+}
+
+// 导出操作日志, 边查询边发送
+pub async fn oper_log_export(Json(item): Json<OperLogFilter>) -> axum::response::Response {
+    match oper_log_service::oper_log_export(item).await {
+        Ok(rows) => (
+            [
+                (header::CONTENT_TYPE, "text/csv; charset=utf-8"),
+                (header::CONTENT_DISPOSITION, "attachment; filename=\"oper_log.csv\""),
+            ],
+            Body::from_stream(rows),
+        ).into_response(),
+        Err(e) => Response::<()>::err(e).into_response(),
+    }
+}
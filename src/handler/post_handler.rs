@@ -0,0 +1,52 @@
+use axum::{Json, Router};
+use axum::response::IntoResponse;
+use axum::routing::post;
+use log::info;
+
+use crate::service::post_service;
+use crate::vo::*;
+use crate::vo::post_vo::*;
+
+pub fn router() -> Router
+{
+    Router::new()
+        .route("/post_list", post(post_list))
+        .route("/post_save", post(post_save))
+        .route("/post_delete", post(post_delete))
+        .route("/post_update", post(post_update))
+}
+
+// 查询岗位列表
+pub async fn post_list(Json(item): Json<PostListReq>) -> impl IntoResponse {
+    info!("post_list params: {:?}", &item);
+
+    let result = post_service::post_list(item).await;
+    let total = result.as_ref().map_or(0, |data| data.total);
+    // 转换成前端需要的格式
+    let result = result.map(|data| data.records);
+    Response::result_page(result, total)
+}
+
+// 添加岗位信息
+pub async fn post_save(Json(item): Json<PostSaveReq>) -> impl IntoResponse {
+    info!("post_save params: {:?}", &item);
+
+    let result = post_service::post_save(item).await;
+    Response::result(result)
+}
+
+// 更新岗位信息
+pub async fn post_update(Json(item): Json<PostUpdateReq>) -> impl IntoResponse {
+    info!("post_update params: {:?}", &item);
+
+    let result = post_service::post_update(item).await;
+    Response::result(result)
+}
+
+// 删除岗位信息
+pub async fn post_delete(Json(item): Json<PostDeleteReq>) -> impl IntoResponse {
+    info!("post_delete params: {:?}", &item);
+
+    let result = post_service::post_delete(item).await;
+    Response::result(result)
+}
@@ -12,6 +12,8 @@ pub fn router() -> Router
     Router::new()
         .route("/query_role_menu", post(query_role_menu))
         .route("/update_role_menu", post(update_role_menu))
+        .route("/query_role_dept", post(query_role_dept))
+        .route("/update_role_dept", post(update_role_dept))
         .route("/role_list", post(role_list))
         .route("/role_save", post(role_save))
         .route("/role_delete", post(role_delete))
@@ -67,3 +69,17 @@ pub async fn update_role_menu(Json(item): Json<UpdateRoleMenuReq>) -> impl IntoR
     let result = role_service::update_role_menu(item).await;
     Response::result(result)
 }
+
+// 查询角色自定义数据范围的部门
+pub async fn query_role_dept(Json(item): Json<QueryRoleDeptReq>) -> impl IntoResponse {
+    info!("query_role_dept params: {:?}", &item);
+    let result = role_service::query_role_dept(item).await;
+    Response::result(result)
+}
+
+// 更新角色自定义数据范围的部门
+pub async fn update_role_dept(Json(item): Json<UpdateRoleDeptReq>) -> impl IntoResponse {
+    info!("update_role_dept params: {:?}", &item);
+    let result = role_service::update_role_dept(item).await;
+    Response::result(result)
+}
@@ -1,14 +1,14 @@
 
 use std::time::Duration;
 
-use axum::http::{Request, Response};
-use axum::{middleware, Router};
+use axum::http::{header, HeaderName, Request, Response};
+use axum::{middleware, Json, Router};
 use axum::response::IntoResponse;
 use axum::routing::get;
 use tower_http::{cors::{Any, CorsLayer}, services::{ServeDir, ServeFile}, trace::TraceLayer};
 
-use crate::{middleware::auth::auth, pool};
-use super::{menu_handler, role_handler, user_handler};
+use crate::{middleware::{auth::{auth, REFRESH_TOKEN_HEADER}, oper_log::oper_log, rate_limit::{rate_limit, user_rate_limit}}, pool, utils::jwt_util};
+use super::{api_key_handler, config_handler, dept_handler, dict_handler, login_log_handler, menu_handler, oidc_handler, oper_log_handler, post_handler, role_handler, session_handler, user_handler};
 
 pub fn app() -> Router {
     // let app_state = Arc::new(AppState{batis: CONTEXT.rb.clone() });
@@ -22,7 +22,8 @@ pub fn app() -> Router {
         .on_response(|response: &Response<_>, latency: Duration, _span: &_| {
             log::debug!("on_response: {:?}ms, {:?}", latency.as_millis(), response.status());
         });
-    let cors_layer = CorsLayer::new().allow_methods(Any).allow_origin(Any).allow_headers(Any);
+    let cors_layer = CorsLayer::new().allow_methods(Any).allow_origin(Any).allow_headers(Any)
+        .expose_headers([HeaderName::from_static(REFRESH_TOKEN_HEADER), header::RETRY_AFTER]);
     
     Router::new()
 
@@ -30,10 +31,25 @@ pub fn app() -> Router {
         .merge(user_handler::router())
         .merge(role_handler::router())
         .merge(menu_handler::router())
+        .merge(dept_handler::router())
+        .merge(post_handler::router())
+        .merge(dict_handler::router())
+        .merge(config_handler::router())
+        .merge(oper_log_handler::router())
+        .merge(login_log_handler::router())
+        .merge(session_handler::router())
+        .merge(api_key_handler::router())
+        .merge(oidc_handler::router())
         // .with_state(app_state)
     )
+        // route_layer wraps the previous ones: rate_limit by ip runs first,
+        // oper_log also records the requests auth rejects
+        .route_layer(middleware::from_fn(user_rate_limit))
         .route_layer(middleware::from_fn(auth))
+        .route_layer(middleware::from_fn(oper_log))
+        .route_layer(middleware::from_fn(rate_limit))
         .route("/status", get(db_status))
+        .route("/.well-known/jwks.json", get(jwks))
         .layer(trace_layer)
         .layer(cors_layer)
         .merge(static_file())
@@ -45,6 +61,10 @@ async fn db_status() -> impl IntoResponse {
 
 }
 
+async fn jwks() -> impl IntoResponse {
+    ([(header::CACHE_CONTROL, "public, max-age=300")], Json(jwt_util::jwks()))
+}
+
 pub fn static_file() -> Router {
     Router::new()
     .nest_service("/", ServeDir::new("dist/")
@@ -0,0 +1,39 @@
+use axum::{Json, Router};
+use axum::response::IntoResponse;
+use axum::routing::post;
+use log::info;
+
+use crate::service::session_service;
+use crate::vo::Response;
+use crate::vo::session_vo::*;
+
+pub fn router() -> Router {
+    Router::new()
+        .route("/session_list", post(session_list))
+        .route("/online_user_list", post(online_user_list))
+        .route("/session_kick", post(session_kick))
+}
+
+// 查询用户的登录会话
+pub async fn session_list(Json(item): Json<SessionListReq>) -> impl IntoResponse {
+    info!("session_list params: {:?}", &item);
+
+    let result = session_service::list(item.user_id).await;
+    let total = result.as_ref().map_or(0, |data| data.len() as u64);
+    Response::result_page(result, total)
+}
+
+// 查询在线用户
+pub async fn online_user_list() -> impl IntoResponse {
+    let result = session_service::online_users().await;
+    let total = result.as_ref().map_or(0, |data| data.len() as u64);
+    Response::result_page(result, total)
+}
+
+// 强制会话下线
+pub async fn session_kick(Json(item): Json<SessionKickReq>) -> impl IntoResponse {
+    info!("session_kick params: {:?}", &item);
+
+    let result = session_service::kick(&item.session_ids).await;
+    Response::result(result)
+}
\ No newline at end of file
@@ -3,28 +3,113 @@ use axum::response::IntoResponse;
 use axum::Router;
 use axum::routing::{get, post};
 use log::info;
-use crate::service::user_service;
-use crate::middleware::context::UserContext;
+use crate::service::{captcha_service, totp_service, user_service};
+use crate::middleware::context::{ClientInfo, UserContext};
+use crate::utils::jwt_util::JWTToken;
 use crate::vo::*;
 use crate::vo::user_vo::*;
 
 pub fn router() -> Router{
     Router::new()
+        .route("/captcha", post(captcha))
         .route("/login", post(login))
+        .route("/send_code", post(send_code))
+        .route("/login_code", post(login_code))
+        .route("/login_totp", post(login_totp))
+        .route("/logout", post(logout))
+        .route("/totp_enroll", post(totp_enroll))
+        .route("/totp_confirm", post(totp_confirm))
+        .route("/totp_disable", post(totp_disable))
+        .route("/totp_reset", post(totp_reset))
+        .route("/refresh_token", post(refresh_token))
         .route("/query_user_role", post(query_user_role))
         .route("/update_user_role", post(update_user_role))
+        .route("/query_user_post", post(query_user_post))
+        .route("/update_user_post", post(update_user_post))
         .route("/query_user_menu", get(query_user_menu))
         .route("/user_list", post(user_list))
         .route("/user_save", post(user_save))
         .route("/user_delete", post(user_delete))
         .route("/user_update", post(user_update))
         .route("/update_user_password", post(update_user_password))
+        .route("/update_expired_password", post(update_expired_password))
+        .route("/locked_user_list", post(locked_user_list))
+        .route("/user_unlock", post(user_unlock))
+        .route("/forgot_password", post(forgot_password))
+        .route("/reset_password", post(reset_password))
 }
 
-// 后台用户登录
-pub async fn login(Json(item): Json<UserLoginReq>) -> impl IntoResponse {
-    info!("user login params: {:?}", &item);
-    let result = user_service::login(item).await;
+// 获取图片验证码
+pub async fn captcha() -> impl IntoResponse {
+    let result = captcha_service::create().await;
+    Response::result(result)
+}
+
+// 后台用户登录, 本地账号的密码可以是md5摘要或明文, LDAP账号必须是明文
+pub async fn login(client: ClientInfo, Json(item): Json<UserLoginReq>) -> impl IntoResponse {
+    info!("user login params: {:?}, client: {:?}", &item, &client);
+    let result = user_service::login(item, client).await;
+    Response::result(result)
+}
+
+// 发送登录验证码
+pub async fn send_code(Json(item): Json<SendCodeReq>) -> impl IntoResponse {
+    info!("send_code params: {:?}", &item);
+    let result = user_service::send_code(item).await;
+    Response::result(result)
+}
+
+// 验证码登录
+pub async fn login_code(client: ClientInfo, Json(item): Json<UserLoginCodeReq>) -> impl IntoResponse {
+    info!("login_code params: {:?}, client: {:?}", &item, &client);
+    let result = user_service::login_code(item, client).await;
+    Response::result(result)
+}
+
+// 两步验证登录
+pub async fn login_totp(client: ClientInfo, Json(item): Json<LoginTotpReq>) -> impl IntoResponse {
+    let result = user_service::login_totp(item, client).await;
+    Response::result(result)
+}
+
+// 开启两步验证, 返回密钥
+pub async fn totp_enroll(content: UserContext) -> impl IntoResponse {
+    info!("totp_enroll user: {:?}", content.id);
+    let result = totp_service::enroll(content.id).await;
+    Response::result(result)
+}
+
+// 确认开启两步验证, 返回恢复码
+pub async fn totp_confirm(content: UserContext, Json(item): Json<TotpCodeReq>) -> impl IntoResponse {
+    info!("totp_confirm user: {:?}", content.id);
+    let result = totp_service::confirm(content.id, &item.code).await;
+    Response::result(result)
+}
+
+// 关闭两步验证
+pub async fn totp_disable(content: UserContext, Json(item): Json<TotpCodeReq>) -> impl IntoResponse {
+    info!("totp_disable user: {:?}", content.id);
+    let result = totp_service::disable(content.id, &item.code).await;
+    Response::result(result)
+}
+
+// 管理员重置用户的两步验证
+pub async fn totp_reset(Json(item): Json<TotpResetReq>) -> impl IntoResponse {
+    info!("totp_reset params: {:?}", &item);
+    let result = totp_service::reset(item.user_id).await;
+    Response::result(result)
+}
+
+// 刷新令牌
+pub async fn refresh_token(client: ClientInfo, Json(item): Json<RefreshTokenReq>) -> impl IntoResponse {
+    let result = user_service::refresh_token(item, client).await;
+    Response::result(result)
+}
+
+// 后台用户退出登录
+pub async fn logout(token: JWTToken) -> impl IntoResponse {
+    info!("user logout: {:?}", token.id);
+    let result = user_service::logout(token).await;
     Response::result(result)
 }
 
@@ -36,9 +121,24 @@ pub async fn query_user_role(Json(item): Json<QueryUserRoleReq>) -> impl IntoRes
 
 }
 
-pub async fn update_user_role(Json(item): Json<UpdateUserRoleReq>) -> impl IntoResponse {
+pub async fn update_user_role(user: UserContext, Json(item): Json<UpdateUserRoleReq>) -> impl IntoResponse {
     info!("update_user_role params: {:?}", item);
-    let result = user_service::update_user_role(item).await;
+    let result = user_service::update_user_role(user.id, item).await;
+    Response::result(result)
+}
+
+// 查询用户的岗位
+pub async fn query_user_post(Json(item): Json<QueryUserPostReq>) -> impl IntoResponse {
+    info!("query_user_post params: {:?}", item);
+
+    let result = user_service::query_user_post(item).await;
+    Response::result(result)
+}
+
+// 更新用户的岗位
+pub async fn update_user_post(user: UserContext, Json(item): Json<UpdateUserPostReq>) -> impl IntoResponse {
+    info!("update_user_post params: {:?}", item);
+    let result = user_service::update_user_post(user.id, item).await;
     Response::result(result)
 }
 
@@ -51,9 +151,9 @@ pub async fn query_user_menu(content: UserContext) -> impl IntoResponse {
 }
 
 // 查询用户列表
-pub async fn user_list(Json(item): Json<UserListReq>) -> impl IntoResponse {
+pub async fn user_list(user: UserContext, Json(item): Json<UserListReq>) -> impl IntoResponse {
     info!("query user_list params: {:?}", &item);
-    let result = user_service::user_list(item).await;
+    let result = user_service::user_list(user.id, item).await;
     let total = result.as_ref().map_or(0, |data| data.total);
     // 转换成前端需要的格式
     let result = result.map(|data| data.records);
@@ -69,17 +169,17 @@ pub async fn user_save(Json(item): Json<UserSaveReq>) -> impl IntoResponse {
 }
 
 // 更新用户信息
-pub async fn user_update(Json(item): Json<UserUpdateReq>) -> impl IntoResponse {
+pub async fn user_update(user: UserContext, Json(item): Json<UserUpdateReq>) -> impl IntoResponse {
     info!("user_update params: {:?}", &item);
 
-    let result = user_service::user_update(item).await;
+    let result = user_service::user_update(user.id, item).await;
     Response::result(result)
 }
 
 // 删除用户信息
-pub async fn user_delete(Json(item): Json<UserDeleteReq>) -> impl IntoResponse {
+pub async fn user_delete(user: UserContext, Json(item): Json<UserDeleteReq>) -> impl IntoResponse {
     info!("user_delete params: {:?}", &item);
-    let result = user_service::user_delete(item).await;
+    let result = user_service::user_delete(user.id, item).await;
     Response::result(result)
 }
 
@@ -89,4 +189,44 @@ pub async fn update_user_password(Json(item): Json<UpdateUserPwdReq>) -> impl In
 
     let result = user_service::update_user_password(item).await;
     Response::result(result)
+}
+
+// 密码过期时修改密码
+pub async fn update_expired_password(Json(item): Json<UpdateExpiredPwdReq>) -> impl IntoResponse {
+    info!("update_expired_password params: {:?}", &item.mobile);
+
+    let result = user_service::update_expired_password(item).await;
+    Response::result(result)
+}
+
+// 查询被锁定的用户
+pub async fn locked_user_list(user: UserContext, Json(item): Json<LockedUserListReq>) -> impl IntoResponse {
+    info!("locked_user_list params: {:?}", &item);
+
+    let result = user_service::locked_user_list(user.id, item).await;
+    let total = result.as_ref().map_or(0, |data| data.total);
+    let result = result.map(|data| data.records);
+    Response::result_page(result, total)
+}
+
+// 解锁用户
+pub async fn user_unlock(user: UserContext, Json(item): Json<UserUnlockReq>) -> impl IntoResponse {
+    info!("user_unlock params: {:?}", &item);
+
+    let result = user_service::user_unlock(user.id, item).await;
+    Response::result(result)
+}
+
+// 忘记密码
+pub async fn forgot_password(Json(item): Json<ForgotPasswordReq>) -> impl IntoResponse {
+    info!("forgot_password params: {:?}", &item);
+
+    let result = user_service::forgot_password(item).await;
+    Response::result(result)
+}
+
+// 重置密码
+pub async fn reset_password(Json(item): Json<ResetPasswordReq>) -> impl IntoResponse {
+    let result = user_service::reset_password(item).await;
+    Response::result(result)
 }
\ No newline at end of file
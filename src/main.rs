@@ -11,10 +11,12 @@ pub mod middleware;
 pub mod service;
 pub mod error;
 
+use std::net::SocketAddr;
+
 use handler::root::*;
 use rbatis::RBatis;
 use log::info;
-use crate::service::CONTEXT;
+use crate::service::{config_service, CONTEXT};
 
 #[cfg(not(target_env = "msvc"))]
 use tikv_jemallocator::Jemalloc;
@@ -31,6 +33,10 @@ pub struct AppState {
 async fn main() {
     log4rs::init_file("config/log4rs.yaml", Default::default()).unwrap();
     CONTEXT.init_database().await;
+    if let Err(e) = config_service::reload().await {
+        log::warn!("load sys_config fail, use application.yaml: {}", e.msg());
+    }
+    config_service::spawn_sync();
 
     
     let app = app();
@@ -38,7 +44,7 @@ async fn main() {
     let addr = CONTEXT.config.addr.as_str();
     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
     info!("listening on {}", addr);
-    axum::serve(listener, app).await.unwrap();
+    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>()).await.unwrap();
 
 }
 
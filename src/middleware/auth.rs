@@ -1,20 +1,55 @@
+use std::sync::OnceLock;
 
 use axum::extract::Request;
 use axum::http::{HeaderValue, StatusCode};
 use axum::middleware::Next;
-use axum::response;
+use axum::response::{self, IntoResponse};
 use log::info;
-use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 
 use crate::middleware::context::UserContext;
+use crate::service::{api_key_service, session_service, token_service, CONTEXT};
 use crate::utils::jwt_util::JWTToken;
+use crate::utils::permission::{MatcherCache, PermissionMatcher};
+
+/// response header carrying the re-signed token when the old one is about to expire
+pub const REFRESH_TOKEN_HEADER: &str = "x-refresh-token";
+
+/// request header carrying an api key, used instead of the bearer token by machine clients
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// anonymous api from `white_list_api`
+fn white_list() -> &'static PermissionMatcher {
+    static WHITE_LIST: OnceLock<PermissionMatcher> = OnceLock::new();
+    WHITE_LIST.get_or_init(|| PermissionMatcher::new(&CONTEXT.config.white_list_api))
+}
+
+/// api that only need a valid token from `auth_only_api`, not available to api keys
+fn auth_only() -> &'static PermissionMatcher {
+    static AUTH_ONLY: OnceLock<PermissionMatcher> = OnceLock::new();
+    AUTH_ONLY.get_or_init(|| PermissionMatcher::new(&CONTEXT.config.auth_only_api))
+}
+
+/// compiled permissions of the token holders by user id
+fn user_matchers() -> &'static MatcherCache {
+    static USER_MATCHERS: OnceLock<MatcherCache> = OnceLock::new();
+    USER_MATCHERS.get_or_init(MatcherCache::default)
+}
+
+/// compiled permissions of the api keys by key id
+fn api_key_matchers() -> &'static MatcherCache {
+    static API_KEY_MATCHERS: OnceLock<MatcherCache> = OnceLock::new();
+    API_KEY_MATCHERS.get_or_init(MatcherCache::default)
+}
 
 pub async fn auth(jwt_token: Result<JWTToken, String>, mut req: Request, next: Next) -> Result<response::Response, StatusCode> {
     info!("auth req {:?} {:?}", req.method(), req.uri());
-    let path = req.uri().to_string();
-    if path.starts_with("/api/login") {
+    let path = req.uri().path().to_string();
+    if white_list().is_match(req.method(), &path) {
         return Ok(next.run(req).await);
     }
+    if let Some(key) = req.headers().get(API_KEY_HEADER).and_then(|v| v.to_str().ok()).map(String::from) {
+        return auth_api_key(&key, req, next).await;
+    }
     let mut jwt_token = match jwt_token {
         Ok(token) => token,
         Err(err) => {
@@ -23,24 +58,63 @@ pub async fn auth(jwt_token: Result<JWTToken, String>, mut req: Request, next: N
         }
     };
 
-    if let Ok(token) = jwt_token.check_refresh() {
-        let token = format!("Bearer {}", token);
-        req.headers_mut()
-            .insert("Authorization", HeaderValue::from_str(&token).unwrap());
-       
+    if let Err(err) = token_service::check_token(&jwt_token).await {
+        info!("auth failed: {}", err);
+        return Err(StatusCode::UNAUTHORIZED)
+    }
+
+    let refreshed = jwt_token.check_refresh().ok();
+    if refreshed.is_some() {
+        // 会话记录最新令牌的过期时间, 吊销时使用
+        if let Err(e) = session_service::extend(&jwt_token).await {
+            info!("extend session {} err: {}", jwt_token.jti, e.msg());
+        }
     }
     
     // debug!("permissions: {:?}",jwt_token.permissions);
-    let flag = jwt_token.permissions.par_iter().any(|permission| permission == &path);
+    // tokens of an older permission version were rejected above
+    let flag = auth_only().is_match(req.method(), &path)
+        || user_matchers().get(jwt_token.id, jwt_token.ver, &jwt_token.permissions).is_match(req.method(), &path);
     info!("auth req {:?} {:?} flag={}", req.method(), req.uri(), flag);
+    let context = UserContext {
+        id: jwt_token.id,
+    };
     if flag {
-        let context = UserContext {
-            id: jwt_token.id,
-        };
-        req.extensions_mut().insert(context);
-        Ok(next.run(req).await)
+        req.extensions_mut().insert(context.clone());
+        let mut response = next.run(req).await;
+        if let Some(token) = refreshed.and_then(|t| HeaderValue::from_str(&t).ok()) {
+            response.headers_mut().insert(REFRESH_TOKEN_HEADER, token);
+        }
+        Ok(with_user(response, context))
     } else {
-        Err(StatusCode::UNAUTHORIZED)
+        Ok(with_user(StatusCode::UNAUTHORIZED.into_response(), context))
+    }
+
+}
+
+async fn auth_api_key(key: &str, mut req: Request, next: Next) -> Result<response::Response, StatusCode> {
+    let api_key = match api_key_service::authenticate(key).await {
+        Ok(api_key) => api_key,
+        Err(err) => {
+            info!("auth api key failed: {}", err);
+            return Err(StatusCode::UNAUTHORIZED)
+        }
+    };
+    let path = req.uri().path();
+    let matcher = api_key_matchers().get(api_key.key_id, api_key.ver, &api_key.permissions);
+    let context = UserContext {
+        id: api_key.user_id,
+    };
+    if !matcher.is_match(req.method(), path) {
+        info!("auth api key {:?} {:?} denied", req.method(), path);
+        return Ok(with_user(StatusCode::UNAUTHORIZED.into_response(), context))
     }
+    req.extensions_mut().insert(context.clone());
+    Ok(with_user(next.run(req).await, context))
+}
 
+/// the user of the request for the outer layers, e.g. `oper_log`
+fn with_user(mut response: response::Response, context: UserContext) -> response::Response {
+    response.extensions_mut().insert(context);
+    response
 }
\ No newline at end of file
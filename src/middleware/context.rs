@@ -1,5 +1,12 @@
+use std::convert::Infallible;
+use std::net::SocketAddr;
+
+use axum::extract::ConnectInfo;
+use axum::http::header;
 use axum::{extract::FromRequestParts, http::request::Parts};
 
+use crate::service::CONTEXT;
+
 #[derive(Debug, Clone)]
 pub struct UserContext {
     pub id: u64,
@@ -22,4 +29,65 @@ where
         Err("UserContext not found".into())
         
     }
+}
+
+/// client ip and user agent of the request
+#[derive(Debug, Clone, Default)]
+pub struct ClientInfo {
+    pub ip: String,
+    pub user_agent: String,
+}
+
+impl ClientInfo {
+    pub fn from_parts(parts: &Parts) -> Self {
+        let get = |name: &str| parts.headers.get(name).and_then(|v| v.to_str().ok());
+
+        // only behind a reverse proxy the forwarded headers can be trusted
+        let forwarded = if CONTEXT.config.trust_proxy {
+            get("x-forwarded-for")
+                .and_then(|v| v.split(',').next())
+                .or_else(|| get("x-real-ip"))
+                .map(|v| v.trim().to_string())
+        } else {
+            None
+        };
+        let ip = forwarded.or_else(|| {
+            parts.extensions.get::<ConnectInfo<SocketAddr>>().map(|c| c.0.ip().to_string())
+        }).unwrap_or_default();
+
+        Self {
+            ip,
+            user_agent: get(header::USER_AGENT.as_str()).unwrap_or_default().to_string(),
+        }
+    }
+
+    /// coarse platform from the user agent
+    pub fn device(&self) -> &'static str {
+        let ua = self.user_agent.as_str();
+        if ua.contains("Android") {
+            "Android"
+        } else if ua.contains("iPhone") || ua.contains("iPad") {
+            "iOS"
+        } else if ua.contains("Windows") {
+            "Windows"
+        } else if ua.contains("Mac OS") {
+            "macOS"
+        } else if ua.contains("Linux") {
+            "Linux"
+        } else {
+            "Other"
+        }
+    }
+}
+
+#[axum::async_trait]
+impl<S> FromRequestParts<S> for ClientInfo
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(Self::from_parts(parts))
+    }
 }
\ No newline at end of file
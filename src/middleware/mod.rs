@@ -1,3 +1,4 @@
 pub mod auth;
 pub mod context;
-
+pub mod rate_limit;
+pub mod oper_log;
\ No newline at end of file
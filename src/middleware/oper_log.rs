@@ -0,0 +1,89 @@
+use std::sync::OnceLock;
+use std::time::Instant;
+
+use axum::body::{to_bytes, Body, HttpBody};
+use axum::extract::Request;
+use axum::http::{header, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use log::warn;
+use rbatis::rbdc::datetime::DateTime;
+
+use crate::middleware::context::{ClientInfo, UserContext};
+use crate::model::oper_log::SysOperLog;
+use crate::service::{oper_log_service, CONTEXT};
+use crate::utils::permission::PermissionMatcher;
+use crate::utils::redact::redact_body;
+
+/// same as the default body limit of the `Json` extractor, also the limit of a read response
+const MAX_BODY: usize = 2 * 1024 * 1024;
+
+/// `oper_log.exclude` matcher
+fn excludes() -> &'static PermissionMatcher {
+    static EXCLUDES: OnceLock<PermissionMatcher> = OnceLock::new();
+    EXCLUDES.get_or_init(|| PermissionMatcher::new(&CONTEXT.config.oper_log.exclude))
+}
+
+/// record who called which api with what result into `sys_oper_log`.
+/// runs before `auth` so rejected requests are recorded too, the user comes from the response.
+/// the row is written in the background
+pub async fn oper_log(client: ClientInfo, req: Request, next: Next) -> Response {
+    let config = &CONTEXT.config.oper_log;
+    if !config.enabled || excludes().is_match(req.method(), req.uri().path()) {
+        return next.run(req).await;
+    }
+    let start = Instant::now();
+    let method = req.method().to_string();
+    let path = req.uri().path().to_string();
+
+    let (parts, body) = req.into_parts();
+    let bytes = match to_bytes(body, MAX_BODY).await {
+        Ok(bytes) => bytes,
+        Err(_) => return StatusCode::PAYLOAD_TOO_LARGE.into_response(),
+    };
+    let req_body = redact_body(&bytes, &config.redact_fields, config.max_body);
+    let response = next.run(Request::from_parts(parts, Body::from(bytes))).await;
+
+    // only json responses of a known size up to MAX_BODY are read for the code,
+    // downloads and streams pass through
+    let is_json = response.headers().get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("application/json"));
+    let is_small = response.body().size_hint().upper().is_some_and(|n| n <= MAX_BODY as u64);
+    let status = response.status().as_u16() as i32;
+    let user_id = response.extensions().get::<UserContext>().map(|u| u.id);
+    let (response, code) = if is_json && is_small {
+        let (parts, body) = response.into_parts();
+        match to_bytes(body, MAX_BODY).await {
+            Ok(bytes) => {
+                let code = serde_json::from_slice::<serde_json::Value>(&bytes).ok()
+                    .and_then(|v| v.get("code")?.as_i64())
+                    .map(|c| c as i32);
+                (Response::from_parts(parts, Body::from(bytes)), code)
+            }
+            Err(_) => return StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+        }
+    } else {
+        (response, None)
+    };
+
+    let log = SysOperLog {
+        id: None,
+        create_time: Some(DateTime::now()),
+        user_id,
+        method,
+        path,
+        req_body,
+        status,
+        code,
+        ip: client.ip,
+        user_agent: client.user_agent,
+        latency_ms: start.elapsed().as_millis() as u64,
+    };
+    tokio::spawn(async move {
+        if let Err(e) = oper_log_service::save(&log).await {
+            warn!("save oper_log fail: {}, {:?}", e, log);
+        }
+    });
+    response
+}
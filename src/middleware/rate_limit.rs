@@ -0,0 +1,91 @@
+use std::sync::OnceLock;
+
+use axum::extract::Request;
+use axum::http::{header, HeaderValue, Method, StatusCode};
+use axum::middleware::Next;
+use axum::response::{self, IntoResponse};
+use log::info;
+
+use crate::config::{RateLimitConfig, RateLimitKey};
+use crate::middleware::context::{ClientInfo, UserContext};
+use crate::service::CONTEXT;
+use crate::utils::permission::PermissionMatcher;
+use crate::vo::BaseResponse;
+
+const CACHE_KEY_RATE_LIMIT: &str = "rate_limit:";
+
+/// `rate_limits` with their path matchers
+fn rules() -> &'static Vec<(PermissionMatcher, &'static RateLimitConfig)> {
+    static RULES: OnceLock<Vec<(PermissionMatcher, &'static RateLimitConfig)>> = OnceLock::new();
+    RULES.get_or_init(|| {
+        CONTEXT
+            .config
+            .rate_limits
+            .iter()
+            .map(|rule| (PermissionMatcher::new(&[rule.path.as_str()]), rule))
+            .collect()
+    })
+}
+
+/// the `ip` and `route` limits of `rate_limits`, every matching rule takes a token.
+/// runs before `auth` so requests failing authentication are limited too
+pub async fn rate_limit(client: ClientInfo, req: Request, next: Next) -> response::Response {
+    let (method, path) = (req.method().clone(), req.uri().path().to_string());
+    let wait_sec = take_tokens(&method, &path, |rule| match rule.key {
+        RateLimitKey::Ip => Some(format!("ip:{}", client.ip)),
+        RateLimitKey::Route => Some(format!("route:{}", path)),
+        RateLimitKey::User => None,
+    }).await;
+    match wait_sec {
+        Some(wait_sec) => too_many_requests(wait_sec),
+        None => next.run(req).await,
+    }
+}
+
+/// the `user` limits of `rate_limits`, runs after `auth` for the user.
+/// anonymous requests fall back to the ip
+pub async fn user_rate_limit(client: ClientInfo, req: Request, next: Next) -> response::Response {
+    let (method, path) = (req.method().clone(), req.uri().path().to_string());
+    let user_id = req.extensions().get::<UserContext>().map(|u| u.id);
+    let wait_sec = take_tokens(&method, &path, |rule| match (&rule.key, user_id) {
+        (RateLimitKey::User, Some(user_id)) => Some(format!("user:{}", user_id)),
+        (RateLimitKey::User, None) => Some(format!("ip:{}", client.ip)),
+        _ => None,
+    }).await;
+    match wait_sec {
+        Some(wait_sec) => too_many_requests(wait_sec),
+        None => next.run(req).await,
+    }
+}
+
+/// take a token from the bucket of every matching rule `key_of` gives a key for,
+/// the seconds to wait when one of them is empty
+async fn take_tokens(method: &Method, path: &str, key_of: impl Fn(&RateLimitConfig) -> Option<String>) -> Option<u64> {
+    for (matcher, rule) in rules() {
+        if !matcher.is_match(method, path) {
+            continue;
+        }
+        let Some(key) = key_of(rule) else {
+            continue;
+        };
+        let key = format!("{}{}:{}", CACHE_KEY_RATE_LIMIT, rule.path, key);
+        match CONTEXT.cache_service.take_token(&key, rule.capacity, rule.rate).await {
+            Ok(0) => {}
+            Ok(wait_sec) => {
+                info!("rate limited {} {}", key, wait_sec);
+                return Some(wait_sec);
+            }
+            // the cache is down, don't block the api
+            Err(e) => info!("rate_limit take_token err: {}", e),
+        }
+    }
+    None
+}
+
+fn too_many_requests(wait_sec: u64) -> response::Response {
+    let msg = CONTEXT.config.get_error_info("req_frequently").replace("{}", &wait_sec.to_string());
+    let mut response = BaseResponse::<()>::err(429, msg).into_response();
+    *response.status_mut() = StatusCode::TOO_MANY_REQUESTS;
+    response.headers_mut().insert(header::RETRY_AFTER, HeaderValue::from(wait_sec));
+    response
+}
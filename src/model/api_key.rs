@@ -0,0 +1,38 @@
+use rbatis::RBatis;
+use rbatis::rbdc::datetime::DateTime;
+use rbatis::rbdc::db::ExecResult;
+use serde::{Deserialize, Serialize};
+
+// api_key
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SysApiKey {
+    pub id: Option<u64>,
+    pub create_time: Option<DateTime>,
+    pub update_time: Option<DateTime>,
+    pub user_id: u64,
+    pub key_name: String,
+    /// first characters of the key, to tell keys apart in the list
+    pub key_prefix: String,
+    /// sha256 of the key, the key itself is only shown at creation
+    pub key_hash: String,
+    /// json array of api_url, a subset of the owner's permissions
+    pub permissions: String,
+    pub expire_time: Option<DateTime>,
+    pub last_used_time: Option<DateTime>,
+
+}
+
+rbatis::crud!(SysApiKey {});
+
+impl_select!(SysApiKey{select_by_hash(key_hash:&str) -> Option => "`where key_hash = #{key_hash} limit 1`"});
+
+impl_select!(SysApiKey{select_by_user(user_id:u64) => "`where user_id = #{user_id} order by create_time desc`"});
+
+impl_delete!(SysApiKey{delete_by_user(user_id:u64, ids:&[u64]) => "`where user_id = #{user_id} and id in ${ids.sql()}`"});
+
+impl SysApiKey {
+    #[py_sql("update sys_api_key set last_used_time = #{time} where id = #{id}")]
+    pub async fn update_last_used(rb: &RBatis, id: u64, time: DateTime) -> Result<ExecResult, rbatis::Error> {
+    }
+
+}
@@ -0,0 +1,40 @@
+use rbatis::RBatis;
+use rbatis::rbdc::datetime::DateTime;
+use rbatis::rbdc::db::ExecResult;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SysDept {
+    pub id: Option<i32>,
+    pub create_time: Option<DateTime>,
+    pub update_time: Option<DateTime>,
+    pub status_id: i32,
+    pub sort: i32,
+    pub parent_id: i32,
+    /// ids from the root to the parent, "0,1,3"
+    pub ancestors: String,
+    pub dept_name: String,
+    pub leader: Option<String>,
+    pub phone: Option<String>,
+    pub remark: Option<String>,
+
+}
+
+rbatis::crud!(SysDept {});
+
+impl_select!(SysDept{select_by_id(id:i32) -> Option => "`where id = #{id} limit 1`"});
+
+// 按名称模糊查询部门
+impl_select!(SysDept{select_by_name(dept_name:&str) => "`where dept_name like concat('%', #{dept_name}, '%')`"});
+
+// 查询部门及其所有下级部门
+impl_select!(SysDept{select_subtree(id:i32) => "`where id = #{id} or find_in_set(#{id}, ancestors)`"});
+
+impl SysDept {
+    // 部门移动后, 更新所有下级部门的ancestors
+    #[py_sql("update sys_dept set ancestors = concat(#{new_prefix}, substring(ancestors, char_length(#{old_prefix}) + 1))
+        where find_in_set(#{id}, ancestors)")]
+    pub async fn update_children_ancestors(rb: &RBatis, id: i32, old_prefix: &str, new_prefix: &str) -> Result<ExecResult, rbatis::Error> {
+    }
+
+}
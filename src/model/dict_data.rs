@@ -0,0 +1,43 @@
+use rbatis::RBatis;
+use rbatis::rbdc::datetime::DateTime;
+use rbatis::rbdc::db::ExecResult;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SysDictData {
+    pub id: Option<i32>,
+    pub create_time: Option<DateTime>,
+    pub update_time: Option<DateTime>,
+    pub status_id: i32,
+    pub sort: i32,
+    pub dict_type: String,
+    pub dict_label: String,
+    pub dict_value: String,
+    /// style of the label in the frontend, "success", "danger"
+    pub list_class: Option<String>,
+    pub remark: Option<String>,
+
+}
+
+rbatis::crud!(SysDictData {});
+
+impl_select_page!(SysDictData{select_page_by_type(dict_type: &str, dict_label: &str, status_id: &str) =>"
+      where dict_type = #{dict_type}
+     if dict_label != null && dict_label != '':
+       ` and dict_label = #{dict_label} `
+     if status_id != null && status_id != '':
+       ` and status_id = #{status_id} `
+     if !sql.contains('count'):
+        ` order by sort asc `"});
+
+impl_select!(SysDictData{select_by_id(id:i32) -> Option => "`where id = #{id} limit 1`"});
+
+// 查询字典类型下启用的数据
+impl_select!(SysDictData{select_enabled(dict_type:&str) => "`where dict_type = #{dict_type} and status_id = 1 order by sort asc`"});
+
+impl SysDictData {
+    // 字典类型的编码修改后, 同步修改字典数据
+    #[py_sql("update sys_dict_data set dict_type = #{new_type} where dict_type = #{old_type}")]
+    pub async fn update_type(rb: &RBatis, old_type: &str, new_type: &str) -> Result<ExecResult, rbatis::Error> {
+    }
+}
@@ -0,0 +1,32 @@
+use rbatis::rbdc::datetime::DateTime;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SysDictType {
+    pub id: Option<i32>,
+    pub create_time: Option<DateTime>,
+    pub update_time: Option<DateTime>,
+    pub status_id: i32,
+    pub dict_name: String,
+    /// unique code the dict data refers to, "sys_status"
+    pub dict_type: String,
+    pub remark: Option<String>,
+
+}
+
+rbatis::crud!(SysDictType {});
+
+impl_select_page!(SysDictType{select_page_by_name(dict_name: &str, dict_type: &str, status_id: &str) =>"
+      where 1=1
+     if dict_name != null && dict_name != '':
+       ` and dict_name = #{dict_name} `
+     if dict_type != null && dict_type != '':
+       ` and dict_type = #{dict_type} `
+     if status_id != null && status_id != '':
+       ` and status_id = #{status_id} `
+     if !sql.contains('count'):
+        ` order by create_time desc `"});
+
+impl_select!(SysDictType{select_by_id(id:i32) -> Option => "`where id = #{id} limit 1`"});
+
+impl_select!(SysDictType{select_by_type(dict_type:&str) -> Option => "`where dict_type = #{dict_type} limit 1`"});
@@ -0,0 +1,52 @@
+use rbatis::rbdc::datetime::DateTime;
+use serde::{Deserialize, Serialize};
+
+pub const LOGIN_TYPE_PASSWORD: &str = "password";
+pub const LOGIN_TYPE_CODE: &str = "code";
+pub const LOGIN_TYPE_OIDC: &str = "oidc";
+pub const LOGIN_TYPE_TOTP: &str = "totp";
+
+pub const LOGIN_STATUS_FAIL: i32 = 0;
+pub const LOGIN_STATUS_SUCCESS: i32 = 1;
+/// the first factor passed, waiting for the totp code
+pub const LOGIN_STATUS_CHALLENGE: i32 = 2;
+
+/// `reason` of a successful login, failures use the error code of `errors.yaml`
+pub const LOGIN_REASON_SUCCESS: &str = "success";
+pub const LOGIN_REASON_CHALLENGE: &str = "challenge";
+/// a failure without an error code
+pub const LOGIN_REASON_ERROR: &str = "error";
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SysLoginLog {
+    pub id: Option<u64>,
+    pub create_time: Option<DateTime>,
+    /// none when the account does not exist
+    pub user_id: Option<u64>,
+    /// the mobile the user logged in with
+    pub account: String,
+    pub login_type: String,
+    pub status: i32,
+    /// one of the LOGIN_REASON_* values or the error code of the failure
+    pub reason: String,
+    /// reason of the failure
+    pub msg: String,
+    pub ip: String,
+    pub user_agent: String,
+
+}
+
+rbatis::crud!(SysLoginLog {});
+
+impl_select_page!(SysLoginLog{select_page_by_filter(account: &str, status: Option<i32>, begin_time: &str, end_time: &str) =>"
+      where 1=1
+     if account != null && account != '':
+       ` and account = #{account} `
+     if status != null:
+       ` and status = #{status} `
+     if begin_time != null && begin_time != '':
+       ` and create_time >= #{begin_time} `
+     if end_time != null && end_time != '':
+       ` and create_time <= #{end_time} `
+     if !sql.contains('count'):
+        ` order by id desc `"});
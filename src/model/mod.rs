@@ -5,3 +5,15 @@ pub mod menu;
 pub mod user_role;
 pub mod role_menu;
 pub mod trash;
+pub mod api_key;
+pub mod password_history;
+pub mod user_oidc;
+pub mod dept;
+pub mod role_dept;
+pub mod post;
+pub mod user_post;
+pub mod dict_type;
+pub mod dict_data;
+pub mod sys_config;
+pub mod oper_log;
+pub mod login_log;
@@ -0,0 +1,56 @@
+use rbatis::rbdc::datetime::DateTime;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SysOperLog {
+    pub id: Option<u64>,
+    pub create_time: Option<DateTime>,
+    /// none for anonymous api
+    pub user_id: Option<u64>,
+    pub method: String,
+    pub path: String,
+    /// redacted and truncated request body
+    pub req_body: String,
+    /// http status of the response
+    pub status: i32,
+    /// `code` of the BaseResponse, none when the response is not json
+    pub code: Option<i32>,
+    pub ip: String,
+    pub user_agent: String,
+    pub latency_ms: u64,
+
+}
+
+rbatis::crud!(SysOperLog {});
+
+impl_select_page!(SysOperLog{select_page_by_filter(user_id: Option<u64>, path: &str, code: Option<i32>, begin_time: &str, end_time: &str) =>"
+      where 1=1
+     if user_id != null:
+       ` and user_id = #{user_id} `
+     if path != null && path != '':
+       ` and path like concat(#{path}, '%') `
+     if code != null:
+       ` and code = #{code} `
+     if begin_time != null && begin_time != '':
+       ` and create_time >= #{begin_time} `
+     if end_time != null && end_time != '':
+       ` and create_time <= #{end_time} `
+     if !sql.contains('count'):
+        ` order by id desc `"});
+
+// 导出时按id分批查询, before_id为上一批的最小id
+impl_select!(SysOperLog{select_by_filter(user_id: Option<u64>, path: &str, code: Option<i32>, begin_time: &str, end_time: &str, before_id: Option<u64>, limit: u64) =>"
+      where 1=1
+     if before_id != null:
+       ` and id < #{before_id} `
+     if user_id != null:
+       ` and user_id = #{user_id} `
+     if path != null && path != '':
+       ` and path like concat(#{path}, '%') `
+     if code != null:
+       ` and code = #{code} `
+     if begin_time != null && begin_time != '':
+       ` and create_time >= #{begin_time} `
+     if end_time != null && end_time != '':
+       ` and create_time <= #{end_time} `
+     ` order by id desc limit #{limit} `"});
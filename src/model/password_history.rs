@@ -0,0 +1,19 @@
+use rbatis::rbdc::datetime::DateTime;
+use serde::{Deserialize, Serialize};
+
+// password_history
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SysPasswordHistory {
+    pub id: Option<u64>,
+    pub create_time: Option<DateTime>,
+    pub user_id: u64,
+    /// hash of a password the user has set
+    pub password: String,
+
+}
+
+rbatis::crud!(SysPasswordHistory {});
+
+impl_select!(SysPasswordHistory{select_recent(user_id:u64, limit:u64) => "`where user_id = #{user_id} order by id desc limit #{limit}`"});
+
+impl_delete!(SysPasswordHistory{delete_before(user_id:u64, id:u64) => "`where user_id = #{user_id} and id < #{id}`"});
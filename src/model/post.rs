@@ -0,0 +1,26 @@
+use rbatis::rbdc::datetime::DateTime;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SysPost {
+    pub id: Option<i32>,
+    pub create_time: Option<DateTime>,
+    pub update_time: Option<DateTime>,
+    pub status_id: i32,
+    pub sort: i32,
+    pub post_code: String,
+    pub post_name: String,
+    pub remark: Option<String>,
+
+}
+
+rbatis::crud!(SysPost {});
+
+impl_select_page!(SysPost{select_page_by_name(post_name: &str, status_id: &str) =>"
+      where 1=1
+     if post_name != null && post_name != '':
+       ` and post_name = #{post_name} `
+     if status_id != null && status_id != '':
+       ` and status_id = #{status_id} `
+     if !sql.contains('count'):
+        ` order by sort asc, create_time desc `"});
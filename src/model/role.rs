@@ -1,6 +1,17 @@
 use rbatis::rbdc::datetime::DateTime;
 use serde::{Deserialize, Serialize};
 
+/// 数据范围: 全部数据
+pub const DATA_SCOPE_ALL: i32 = 1;
+/// 数据范围: 自定义部门
+pub const DATA_SCOPE_CUSTOM: i32 = 2;
+/// 数据范围: 本部门
+pub const DATA_SCOPE_DEPT: i32 = 3;
+/// 数据范围: 本部门及以下
+pub const DATA_SCOPE_DEPT_AND_CHILD: i32 = 4;
+/// 数据范围: 仅本人
+pub const DATA_SCOPE_SELF: i32 = 5;
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SysRole {
     pub id: Option<i32>,
@@ -10,6 +21,10 @@ pub struct SysRole {
     pub sort: i32,
     pub role_name: String,
     pub remark: Option<String>,
+    /// users of the role must use two-factor authentication
+    pub require_2fa: i32,
+    /// which users the role can see, one of the DATA_SCOPE_* values
+    pub data_scope: i32,
 
 }
 
@@ -26,3 +41,6 @@ impl_select_page!(SysRole{select_page_by_name(role_name: &str, status_id: &str)
        ` and status_id = #{status_id} `
      if !sql.contains('count'):
         ` order by create_time desc `"});
+
+// 查询用户拥有的有效角色
+impl_select!(SysRole{select_by_user(user_id: u64) => "`where status_id = 1 and id in (select role_id from sys_user_role where user_id = #{user_id})`"});
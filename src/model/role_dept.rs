@@ -0,0 +1,28 @@
+use rbatis::rbdc::datetime::DateTime;
+use serde::{Deserialize, Serialize};
+
+// 角色自定义数据范围的部门
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SysRoleDept {
+    pub id: Option<i32>,
+    pub create_time: Option<DateTime>,
+    pub update_time: Option<DateTime>,
+    pub role_id: i32,
+    pub dept_id: i32,
+
+}
+
+impl SysRoleDept {
+    pub fn new(role_id: i32, dept_id: i32) -> Self {
+        let now = Some(DateTime::now());
+        Self {
+            id: None,
+            create_time: now.clone(),
+            update_time: now,
+            role_id,
+            dept_id,
+        }
+    }
+}
+
+rbatis::crud!(SysRoleDept {});
@@ -0,0 +1,38 @@
+use rbatis::rbdc::datetime::DateTime;
+use serde::{Deserialize, Serialize};
+
+/// `value_type` of a config value
+pub const VALUE_TYPE_STRING: &str = "string";
+pub const VALUE_TYPE_NUMBER: &str = "number";
+pub const VALUE_TYPE_BOOL: &str = "bool";
+pub const VALUE_TYPE_JSON: &str = "json";
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SysConfig {
+    pub id: Option<i32>,
+    pub create_time: Option<DateTime>,
+    pub update_time: Option<DateTime>,
+    pub config_name: String,
+    /// a key of the runtime settings overrides `application.yaml`, "jwt_exp"
+    pub config_key: String,
+    pub config_value: String,
+    /// one of the VALUE_TYPE_* values
+    pub value_type: String,
+    pub remark: Option<String>,
+
+}
+
+rbatis::crud!(SysConfig {});
+
+impl_select_page!(SysConfig{select_page_by_name(config_name: &str, config_key: &str) =>"
+      where 1=1
+     if config_name != null && config_name != '':
+       ` and config_name = #{config_name} `
+     if config_key != null && config_key != '':
+       ` and config_key = #{config_key} `
+     if !sql.contains('count'):
+        ` order by create_time desc `"});
+
+impl_select!(SysConfig{select_by_id(id:i32) -> Option => "`where id = #{id} limit 1`"});
+
+impl_select!(SysConfig{select_by_key(config_key:&str) -> Option => "`where config_key = #{config_key} limit 1`"});
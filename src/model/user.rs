@@ -3,6 +3,11 @@ use rbatis::rbdc::datetime::DateTime;
 use rbatis::rbdc::db::ExecResult;
 use serde::{Deserialize, Serialize};
 
+/// `auth_source` of accounts with a local password, also used when empty
+pub const AUTH_SOURCE_LOCAL: &str = "local";
+pub const AUTH_SOURCE_LDAP: &str = "ldap";
+pub const AUTH_SOURCE_OIDC: &str = "oidc";
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SysUser {
     pub id: Option<u64>,
@@ -13,7 +18,21 @@ pub struct SysUser {
     pub mobile: String,
     pub user_name: String,
     pub remark: Option<String>,
+    pub dept_id: Option<i32>,
     pub password: String,
+    /// when the password was last changed, for `password_policy.max_age_days`
+    pub password_update_time: Option<DateTime>,
+    /// where the password is checked, "local", "ldap" or "oidc"
+    pub auth_source: Option<String>,
+    /// login is refused until then, set after repeated login failures
+    pub locked_until: Option<DateTime>,
+    pub lock_reason: Option<String>,
+    /// base32 totp secret, two-factor authentication is on when not empty
+    pub totp_secret: Option<String>,
+    /// json array of hashed recovery codes
+    pub totp_recovery: Option<String>,
+    pub last_login_time: Option<DateTime>,
+    pub last_login_ip: Option<String>,
 
 }
 
@@ -23,15 +42,23 @@ impl_select_page!(SysUser{select_page() =>"
      if !sql.contains('count'):
        order by create_time desc"});
 
-impl_select_page!(SysUser{select_page_by_name(mobile: &str, status_id: &str) =>"
+// dept_id查询部门及其所有下级部门的用户, 数据范围由data_scope_service::scoped限制
+impl_select_page!(SysUser{select_page_by_name(mobile: &str, status_id: &str, dept_id: Option<i32>) =>"
       where 1=1
      if mobile != null && mobile != '' :
        ` and mobile = #{mobile} `
      if status_id != null && status_id != '' :
        ` and status_id = #{status_id} `
+     if dept_id != null :
+       ` and dept_id in (select id from sys_dept where id = #{dept_id} or find_in_set(#{dept_id}, ancestors)) `
      if !sql.contains('count'):
         ` order by create_time desc `"});
 
+impl_select_page!(SysUser{select_page_locked(now: DateTime) =>"
+      where locked_until > #{now}
+     if !sql.contains('count'):
+        ` order by locked_until desc `"});
+
 impl_select!(SysUser{select_by_id(id:u64) -> Option => "`where id = #{id} limit 1`"});
 
 impl_select!(SysUser{select_by_mobile(mobile:&str) -> Option => "`where mobile = #{mobile} limit 1`"});
@@ -40,9 +67,33 @@ impl_select!(SysUser{select_by_mobile(mobile:&str) -> Option => "`where mobile =
 
 
 impl SysUser {
+    pub fn auth_source(&self) -> &str {
+        self.auth_source.as_deref().filter(|s| !s.is_empty()).unwrap_or(AUTH_SOURCE_LOCAL)
+    }
+
+    pub fn is_local(&self) -> bool {
+        self.auth_source() == AUTH_SOURCE_LOCAL
+    }
+
     #[py_sql("update sys_user set password = #{password} where id = #{id}")]
     pub async fn update_password(rb: &RBatis, id: u64, password: &str) -> Result<ExecResult, rbatis::Error> {
     }
 
+    #[py_sql("update sys_user set password = #{password}, password_update_time = #{time} where id = #{id}")]
+    pub async fn change_password(rb: &RBatis, id: u64, password: &str, time: DateTime) -> Result<ExecResult, rbatis::Error> {
+    }
+
+    #[py_sql("update sys_user set locked_until = #{locked_until}, lock_reason = #{lock_reason} where id = #{id}")]
+    pub async fn update_lock(rb: &RBatis, id: u64, locked_until: Option<DateTime>, lock_reason: Option<&str>) -> Result<ExecResult, rbatis::Error> {
+    }
+
+    #[py_sql("update sys_user set last_login_time = #{time}, last_login_ip = #{ip} where id = #{id}")]
+    pub async fn update_last_login(rb: &RBatis, id: u64, time: DateTime, ip: &str) -> Result<ExecResult, rbatis::Error> {
+    }
+
+    #[py_sql("update sys_user set totp_secret = #{totp_secret}, totp_recovery = #{totp_recovery} where id = #{id}")]
+    pub async fn update_totp(rb: &RBatis, id: u64, totp_secret: Option<&str>, totp_recovery: Option<&str>) -> Result<ExecResult, rbatis::Error> {
+    }
+
 }
 
@@ -0,0 +1,17 @@
+use rbatis::rbdc::datetime::DateTime;
+use serde::{Deserialize, Serialize};
+
+// user_oidc, links an identity provider subject to a user
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SysUserOidc {
+    pub id: Option<u64>,
+    pub create_time: Option<DateTime>,
+    pub user_id: u64,
+    pub issuer: String,
+    pub subject: String,
+
+}
+
+rbatis::crud!(SysUserOidc {});
+
+impl_select!(SysUserOidc{select_by_subject(issuer:&str, subject:&str) -> Option => "`where issuer = #{issuer} and subject = #{subject} limit 1`"});
@@ -0,0 +1,28 @@
+use rbatis::rbdc::datetime::DateTime;
+use serde::{Deserialize, Serialize};
+
+// user_post
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SysUserPost {
+    pub id: Option<i32>,
+    pub create_time: Option<DateTime>,
+    pub update_time: Option<DateTime>,
+    pub post_id: i32,
+    pub user_id: u64,
+
+}
+
+impl SysUserPost {
+    pub fn new(user_id: u64, post_id: i32) -> Self {
+        let now = Some(DateTime::now());
+        Self {
+            id: None,
+            create_time: now.clone(),
+            update_time: now,
+            post_id,
+            user_id,
+        }
+    }
+}
+
+rbatis::crud!(SysUserPost {});
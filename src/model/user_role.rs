@@ -1,3 +1,4 @@
+use rbatis::RBatis;
 use rbatis::rbdc::datetime::DateTime;
 use serde::{Deserialize, Serialize};
 
@@ -26,4 +27,10 @@ impl_select_page!(SysUserRole{select_page_by_name(name:&str) =>"
        where user_name != ''"});
 
 // 查询是否为超级管理员(role_id=1是预设超级管理的id)
-impl_select!(SysUserRole{is_admin(user_id:u64) => "`where user_id = #{user_id} and role_id = 1`"});
\ No newline at end of file
+impl_select!(SysUserRole{is_admin(user_id:u64) => "`where user_id = #{user_id} and role_id = 1`"});
+
+// 查询用户是否有要求两步验证的角色
+#[sql("select count(1) from sys_user_role ur left join sys_role r on ur.role_id = r.id where ur.user_id = ? and r.require_2fa = 1")]
+pub async fn count_require_2fa_role(rb: &RBatis, user_id: u64) -> rbatis::Result<u64> {
+    impled!()
+}
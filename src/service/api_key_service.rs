@@ -0,0 +1,166 @@
+use std::collections::HashSet;
+
+use data_encoding::HEXLOWER;
+use log::info;
+use rbatis::rbdc::datetime::DateTime;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::error::Result;
+use crate::model::api_key::SysApiKey;
+use crate::model::user::SysUser;
+use crate::{error_info, pool, Error};
+use crate::service::{token_service, user_service, CONTEXT};
+use crate::utils::{get_timestamp, random_string};
+use crate::vo::api_key_vo::*;
+
+const KEY_PREFIX: &str = "ak_";
+const KEY_LEN: usize = 40;
+/// don't write last_used_time on every request
+const LAST_USED_INTERVAL: i64 = 60;
+const CACHE_KEY_API_KEY: &str = "api_key:";
+// 权限版本变化时缓存失效, 过期时间只是兜底
+const API_KEY_CACHE_TTL: u64 = 300;
+/// the longest lifetime of a key
+const MAX_EXPIRE_DAYS: u64 = 3650;
+
+/// the user and permissions an api key acts with, cached until
+/// the permission version of the owner changes
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ApiKeyAuth {
+    pub key_id: u64,
+    pub user_id: u64,
+    /// permission version of the owner, the permissions are narrowed by it
+    pub ver: i64,
+    pub permissions: Vec<String>,
+    expire_at: Option<i64>,
+    last_used: i64,
+}
+
+/// keys are random, a fast hash is enough and allows lookup by hash
+fn hash_key(key: &str) -> String {
+    HEXLOWER.encode(&Sha256::digest(key.as_bytes()))
+}
+
+fn cache_key(key_hash: &str) -> String {
+    format!("{}{}", CACHE_KEY_API_KEY, key_hash)
+}
+
+// 创建API密钥, 权限不能超出用户自己的权限
+pub async fn save(user_id: u64, item: ApiKeySaveReq) -> Result<ApiKeySaveData> {
+    if item.key_name.trim().is_empty() {
+        return error_info!("api_key_name_empty")
+    }
+    if item.permissions.is_empty() {
+        return error_info!("api_key_permission_empty")
+    }
+    let owned: HashSet<String> = user_service::query_btn_menu(user_id).await.into_iter().collect();
+    if let Some(p) = item.permissions.iter().find(|p| !owned.contains(*p)) {
+        return error_info!("api_key_permission_denied", p.to_string())
+    }
+
+    let expire_time = match item.expire_days {
+        None => None,
+        Some(days) if !(1..=MAX_EXPIRE_DAYS).contains(&days) => {
+            return error_info!("api_key_expire_days_invalid", MAX_EXPIRE_DAYS.to_string())
+        }
+        Some(days) => {
+            let Some(expire_at) = days.checked_mul(86400).and_then(|s| s.checked_add(get_timestamp())) else {
+                return Error::err("有效天数超出范围")
+            };
+            Some(DateTime::from_timestamp(expire_at as i64))
+        }
+    };
+
+    let key = format!("{}{}", KEY_PREFIX, random_string(KEY_LEN));
+    let api_key = SysApiKey {
+        id: None,
+        create_time: Some(DateTime::now()),
+        update_time: Some(DateTime::now()),
+        user_id,
+        key_name: item.key_name,
+        key_prefix: key[..KEY_PREFIX.len() + 4].to_string(),
+        key_hash: hash_key(&key),
+        permissions: serde_json::to_string(&item.permissions).unwrap_or_default(),
+        expire_time,
+        last_used_time: None,
+    };
+
+    let result = SysApiKey::insert(pool!(), &api_key).await?;
+    let id = result.last_insert_id.as_u64().unwrap_or_default();
+    Ok(ApiKeySaveData { id, key })
+}
+
+// 查询用户的API密钥
+pub async fn list(user_id: u64) -> Result<Vec<ApiKeyListData>> {
+    let keys = SysApiKey::select_by_user(pool!(), user_id).await?;
+    Ok(keys.into_iter().map(|k| k.into()).collect())
+}
+
+// 吊销API密钥
+pub async fn delete(user_id: u64, ids: &[u64]) -> Result<u64> {
+    if ids.is_empty() {
+        return Ok(0)
+    }
+    let rb = pool!();
+    let keys = SysApiKey::select_in_column(rb, "id", ids).await?;
+    let result = SysApiKey::delete_by_user(rb, user_id, ids).await?;
+    for key in keys.iter().filter(|k| k.user_id == user_id) {
+        CONTEXT.cache_service.remove(&cache_key(&key.key_hash)).await?;
+    }
+    Ok(result.rows_affected)
+}
+
+/// check the key of a request, the permissions are narrowed to
+/// what the owner still has
+pub async fn authenticate(key: &str) -> Result<ApiKeyAuth> {
+    let key_hash = hash_key(key);
+    let cached: Option<ApiKeyAuth> = CONTEXT.cache_service.get_json(&cache_key(&key_hash)).await?;
+    let (mut auth, mut changed) = match cached {
+        Some(auth) if auth.ver == token_service::perm_version(auth.user_id).await? => (auth, false),
+        _ => (load(&key_hash).await?, true),
+    };
+
+    let now = get_timestamp() as i64;
+    if auth.expire_at.is_some_and(|t| t < now) {
+        return error_info!("api_key_expired")
+    }
+    if now - auth.last_used > LAST_USED_INTERVAL {
+        if let Err(e) = SysApiKey::update_last_used(pool!(), auth.key_id, DateTime::now()).await {
+            info!("update api key last_used_time err: {:?}", e);
+        }
+        auth.last_used = now;
+        changed = true;
+    }
+    if changed {
+        CONTEXT.cache_service.set_json(&cache_key(&key_hash), &auth, API_KEY_CACHE_TTL).await?;
+    }
+    Ok(auth)
+}
+
+// 从数据库加载密钥, 先取权限版本, 期间权限变化时缓存会失效
+async fn load(key_hash: &str) -> Result<ApiKeyAuth> {
+    let rb = pool!();
+    let Some(api_key) = SysApiKey::select_by_hash(rb, key_hash).await? else {
+        return error_info!("api_key_invalid")
+    };
+    let user_id = api_key.user_id;
+    let ver = token_service::perm_version(user_id).await?;
+    match SysUser::select_by_id(rb, user_id).await? {
+        Some(user) if user.status_id == 1 => {}
+        _ => return error_info!("account_disabled"),
+    }
+
+    let owned: HashSet<String> = user_service::query_btn_menu(user_id).await.into_iter().collect();
+    let mut permissions: Vec<String> = serde_json::from_str(&api_key.permissions).unwrap_or_default();
+    permissions.retain(|p| owned.contains(p));
+
+    Ok(ApiKeyAuth {
+        key_id: api_key.id.unwrap_or_default(),
+        user_id,
+        ver,
+        permissions,
+        expire_at: api_key.expire_time.as_ref().map(|t| t.unix_timestamp()),
+        last_used: api_key.last_used_time.as_ref().map_or(0, |t| t.unix_timestamp()),
+    })
+}
@@ -0,0 +1,192 @@
+use ldap3::{ldap_escape, LdapConnAsync, Scope, SearchEntry};
+use log::info;
+use rbatis::rbdc::datetime::DateTime;
+
+use super::Authenticator;
+use crate::config::LdapConfig;
+use crate::error::{Error, Result};
+use crate::model::user::{SysUser, AUTH_SOURCE_LDAP};
+use crate::pool;
+use crate::service::user_service;
+use crate::utils::password::Password;
+
+/// directory accounts, the password is checked by binding as the user
+pub struct LdapAuthenticator {
+    config: &'static LdapConfig,
+}
+
+/// the directory entry of a login
+struct LdapEntry {
+    dn: String,
+    user_name: Option<String>,
+    groups: Vec<String>,
+}
+
+fn ldap_err(e: ldap3::LdapError) -> Error {
+    info!("ldap err: {:?}", e);
+    Error::E("目录服务请求失败".to_string())
+}
+
+impl LdapAuthenticator {
+    pub fn new(config: &'static LdapConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn jit_provision(&self) -> bool {
+        self.config.jit_provision
+    }
+
+    /// find the entry with the service account, then bind as the user
+    async fn bind(&self, mobile: &str, password: &str) -> Result<Option<LdapEntry>> {
+        let config = self.config;
+        let (conn, mut ldap) = LdapConnAsync::new(&config.url).await.map_err(ldap_err)?;
+        ldap3::drive!(conn);
+
+        if !config.bind_dn.is_empty() {
+            ldap.simple_bind(&config.bind_dn, &config.bind_password).await
+                .and_then(|r| r.success()).map_err(ldap_err)?;
+        }
+        let filter = config.user_filter.replace("{}", &ldap_escape(mobile));
+        let attrs = vec![config.name_attr.as_str(), config.group_attr.as_str()];
+        let (entries, _) = ldap.search(&config.base_dn, Scope::Subtree, &filter, attrs).await
+            .and_then(|r| r.success()).map_err(ldap_err)?;
+        let Some(entry) = entries.into_iter().next().map(SearchEntry::construct) else {
+            let _ = ldap.unbind().await;
+            return Ok(None)
+        };
+
+        let bound = ldap.simple_bind(&entry.dn, password).await.map_err(ldap_err)?.success().is_ok();
+        let _ = ldap.unbind().await;
+        if !bound {
+            return Ok(None)
+        }
+        let first = |attr: &str| entry.attrs.get(attr).and_then(|v| v.first()).cloned();
+        Ok(Some(LdapEntry {
+            user_name: first(&config.name_attr),
+            groups: entry.attrs.get(&config.group_attr).cloned().unwrap_or_default(),
+            dn: entry.dn,
+        }))
+    }
+
+    /// a group matches the mapping by its dn or its first rdn value, `cn=admins,ou=groups,...` is also `admins`
+    fn mapped_roles(&self, groups: &[String]) -> Vec<i32> {
+        let names: Vec<String> = groups.iter()
+            .flat_map(|dn| {
+                let rdn = dn.split(',').next().and_then(|rdn| rdn.split_once('=')).map(|(_, v)| v.to_string());
+                std::iter::once(dn.clone()).chain(rdn)
+            })
+            .collect();
+        user_service::map_group_roles(&names, &self.config.role_mapping, &self.config.default_role_ids)
+    }
+
+    async fn provision(&self, mobile: &str, entry: &LdapEntry) -> Result<SysUser> {
+        let now = Some(DateTime::now());
+        let mut user = SysUser {
+            id: None,
+            create_time: now.clone(),
+            update_time: now.clone(),
+            status_id: 1,
+            sort: 1,
+            mobile: mobile.to_string(),
+            user_name: entry.user_name.clone().unwrap_or_else(|| mobile.to_string()),
+            remark: Some(entry.dn.clone()),
+            dept_id: None,
+            // the directory keeps the password
+            password: String::new(),
+            password_update_time: now,
+            auth_source: Some(AUTH_SOURCE_LDAP.to_string()),
+            locked_until: None,
+            lock_reason: None,
+            totp_secret: None,
+            totp_recovery: None,
+            last_login_time: None,
+            last_login_ip: None,
+        };
+        let result = SysUser::insert(pool!(), &user).await?;
+        user.id = result.last_insert_id.as_u64();
+        info!("ldap provision user {:?} {}", user.id, entry.dn);
+        Ok(user)
+    }
+}
+
+#[async_trait]
+impl Authenticator for LdapAuthenticator {
+    async fn authenticate(&self, mobile: &str, password: &str, user: Option<SysUser>) -> Result<Option<SysUser>> {
+        // the bind needs the plain password, a login page hashing it can never log in
+        if Password::is_md5(password) {
+            return Error::err("目录账号需要明文密码,请关闭登录页的密码摘要")
+        }
+        // an empty password is an anonymous bind, which succeeds
+        if password.is_empty() {
+            return Ok(None)
+        }
+        let Some(entry) = self.bind(mobile, password).await? else {
+            return Ok(None)
+        };
+
+        let provisioned = user.is_none();
+        let user = match user {
+            Some(user) => user,
+            None => self.provision(mobile, &entry).await?,
+        };
+        if provisioned || self.config.sync_roles {
+            user_service::sync_user_roles(user.id.unwrap_or_default(), &self.mapped_roles(&entry.groups)).await?;
+        }
+        Ok(Some(user))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    fn config(url: &str) -> &'static LdapConfig {
+        let env = |name: &str, default: &str| std::env::var(name).unwrap_or_else(|_| default.to_string());
+        Box::leak(Box::new(LdapConfig {
+            url: url.to_string(),
+            bind_dn: env("LDAP_BIND_DN", "cn=admin,dc=example,dc=org"),
+            bind_password: env("LDAP_BIND_PASSWORD", "admin"),
+            base_dn: env("LDAP_BASE_DN", "ou=people,dc=example,dc=org"),
+            user_filter: "(mobile={})".to_string(),
+            name_attr: "cn".to_string(),
+            group_attr: "memberOf".to_string(),
+            jit_provision: false,
+            sync_roles: false,
+            default_role_ids: vec![3],
+            role_mapping: HashMap::from([("admins".to_string(), vec![1]), ("cn=ops,ou=groups,dc=example,dc=org".to_string(), vec![2])]),
+        }))
+    }
+
+    #[test]
+    fn test_mapped_roles() {
+        let ldap = LdapAuthenticator::new(config("ldap://localhost:389"));
+        let groups = vec!["cn=admins,ou=groups,dc=example,dc=org".to_string(), "cn=ops,ou=groups,dc=example,dc=org".to_string()];
+        assert_eq!(ldap.mapped_roles(&groups), vec![1, 2, 3]);
+        assert_eq!(ldap.mapped_roles(&[]), vec![3]);
+    }
+
+    #[tokio::test]
+    async fn test_md5_password_refused() {
+        // nothing listens there, the digest is refused before connecting
+        let ldap = LdapAuthenticator::new(config("ldap://127.0.0.1:1"));
+        let result = ldap.authenticate("13800000000", &Password::md5("123456"), None).await;
+        assert_eq!(result.err().map(|e| e.msg()).as_deref(), Some("目录账号需要明文密码,请关闭登录页的密码摘要"));
+        assert!(ldap.authenticate("13800000000", "", None).await.unwrap().is_none());
+    }
+
+    /// needs a directory, e.g. LDAP_URL=ldap://localhost:389 LDAP_MOBILE=13800000000 LDAP_PASSWORD=secret
+    #[ignore]
+    #[tokio::test]
+    async fn test_ldap_bind() {
+        let env = |name: &str| std::env::var(name).unwrap_or_else(|_| panic!("{} not set", name));
+        let ldap = LdapAuthenticator::new(config(&env("LDAP_URL")));
+        let mobile = env("LDAP_MOBILE");
+
+        let entry = ldap.bind(&mobile, &env("LDAP_PASSWORD")).await.unwrap().expect("bind with the password");
+        assert!(!entry.dn.is_empty());
+        assert!(ldap.bind(&mobile, "wrong password").await.unwrap().is_none());
+        assert!(ldap.bind("00000000000", "wrong password").await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,92 @@
+use log::info;
+
+use super::Authenticator;
+use crate::error::Result;
+use crate::model::user::SysUser;
+use crate::pool;
+use crate::utils::password::Password;
+
+/// accounts with a password hash in `sys_user`
+pub struct LocalAuthenticator;
+
+/// stored hashes are of the md5 digest the login page sends,
+/// a client sending the plain password (for the directory) is digested here
+pub fn local_secret(password: &str) -> String {
+    if Password::is_md5(password) {
+        password.to_string()
+    } else {
+        Password::md5(password)
+    }
+}
+
+#[async_trait]
+impl Authenticator for LocalAuthenticator {
+    async fn authenticate(&self, _mobile: &str, password: &str, user: Option<SysUser>) -> Result<Option<SysUser>> {
+        let Some(user) = user else {
+            return Ok(None)
+        };
+        let secret = local_secret(password);
+        if !Password::verify(&secret, &user.password) {
+            return Ok(None)
+        }
+        if Password::needs_rehash(&user.password) {
+            // 旧的bcrypt/md5密码升级为argon2id
+            let result = SysUser::update_password(pool!(), user.id.unwrap(), &Password::hash(&secret)).await;
+            info!("rehash password result: {:?}", result);
+        }
+        Ok(Some(user))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user(password: &str) -> SysUser {
+        SysUser {
+            id: Some(2),
+            create_time: None,
+            update_time: None,
+            status_id: 1,
+            sort: 1,
+            mobile: "13800000000".to_string(),
+            user_name: "test".to_string(),
+            remark: None,
+            dept_id: None,
+            password: password.to_string(),
+            password_update_time: None,
+            auth_source: None,
+            locked_until: None,
+            lock_reason: None,
+            totp_secret: None,
+            totp_recovery: None,
+            last_login_time: None,
+            last_login_ip: None,
+        }
+    }
+
+    #[test]
+    fn test_local_secret() {
+        let digest = Password::md5("123456");
+        assert_eq!(local_secret("123456"), digest);
+        assert_eq!(local_secret(&digest), digest);
+        assert_eq!(local_secret(&digest.to_uppercase()), digest.to_uppercase());
+    }
+
+    #[tokio::test]
+    async fn test_local_authenticate() {
+        let hash = Password::hash(Password::md5("123456"));
+        let local = LocalAuthenticator;
+
+        // the digest of the login page and the plain password both match
+        let user_id = |r: Option<SysUser>| r.and_then(|u| u.id);
+        assert_eq!(user_id(local.authenticate("13800000000", &Password::md5("123456"), Some(user(&hash))).await.unwrap()), Some(2));
+        assert_eq!(user_id(local.authenticate("13800000000", "123456", Some(user(&hash))).await.unwrap()), Some(2));
+
+        assert!(local.authenticate("13800000000", "654321", Some(user(&hash))).await.unwrap().is_none());
+        assert!(local.authenticate("13800000000", "", Some(user(&hash))).await.unwrap().is_none());
+        // accounts without a password only log in elsewhere
+        assert!(local.authenticate("13800000000", "", Some(user(""))).await.unwrap().is_none());
+        assert!(local.authenticate("13800000000", "123456", None).await.unwrap().is_none());
+    }
+}
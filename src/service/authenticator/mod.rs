@@ -0,0 +1,35 @@
+use std::sync::OnceLock;
+
+use crate::error::Result;
+use crate::model::user::{SysUser, AUTH_SOURCE_LDAP, AUTH_SOURCE_LOCAL};
+use crate::service::CONTEXT;
+
+use self::ldap::LdapAuthenticator;
+use self::local::LocalAuthenticator;
+
+pub mod ldap;
+pub mod local;
+
+/// checks the password of a login
+#[async_trait]
+pub trait Authenticator: Sync + Send {
+    /// the user when the password matches, `None` otherwise.
+    /// `user` is the account with the mobile if it exists, an authenticator may create it
+    async fn authenticate(&self, mobile: &str, password: &str, user: Option<SysUser>) -> Result<Option<SysUser>>;
+}
+
+fn ldap() -> Option<&'static LdapAuthenticator> {
+    static LDAP: OnceLock<Option<LdapAuthenticator>> = OnceLock::new();
+    LDAP.get_or_init(|| CONTEXT.config.ldap.as_ref().map(LdapAuthenticator::new)).as_ref()
+}
+
+/// the authenticator of the account, unknown mobiles go to the directory when it provisions users
+pub fn for_user(user: Option<&SysUser>) -> Option<&'static dyn Authenticator> {
+    static LOCAL: LocalAuthenticator = LocalAuthenticator;
+    match user.map(|u| u.auth_source()) {
+        Some(AUTH_SOURCE_LOCAL) => Some(&LOCAL),
+        Some(AUTH_SOURCE_LDAP) => ldap().map(|a| a as &dyn Authenticator),
+        Some(_) => None,
+        None => ldap().filter(|a| a.jit_provision()).map(|a| a as &dyn Authenticator),
+    }
+}
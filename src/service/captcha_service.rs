@@ -0,0 +1,89 @@
+use captcha::Difficulty;
+
+use super::CONTEXT;
+use crate::error::Result;
+use crate::error_info;
+use crate::utils::cache::cache_service::CacheService;
+use crate::utils::random_string;
+use crate::vo::captcha_vo::CaptchaData;
+
+const CACHE_KEY_CAPTCHA: &str = "captcha:";
+const CAPTCHA_TTL: u64 = 300;
+
+/// generate a png captcha, the answer is kept in the cache under a one-time id
+pub async fn create() -> Result<CaptchaData> {
+    let Some((answer, image)) = generate() else {
+        return error_info!("-1")
+    };
+    let captcha_id = random_string(32);
+    CONTEXT
+        .cache_service
+        .set_string(&format!("{}{}", CACHE_KEY_CAPTCHA, captcha_id), &answer, CAPTCHA_TTL)
+        .await?;
+    Ok(CaptchaData {
+        captcha_id,
+        image: format!("data:image/png;base64,{}", image),
+    })
+}
+
+// the captcha is not Send, it must not be held across an await
+fn generate() -> Option<(String, String)> {
+    let captcha = captcha::gen(Difficulty::Easy);
+    let image = captcha.as_base64()?;
+    Some((captcha.chars_as_string().to_lowercase(), image))
+}
+
+/// check the answer, the captcha is removed whether it matches or not
+pub async fn verify(captcha_id: &str, code: &str) -> Result<()> {
+    check(&CONTEXT.cache_service, captcha_id, code).await
+}
+
+async fn check(cache: &CacheService, captcha_id: &str, code: &str) -> Result<()> {
+    // of parallel requests with the same captcha only one gets the answer
+    let answer = cache.take_string(&format!("{}{}", CACHE_KEY_CAPTCHA, captcha_id)).await?;
+    if answer.is_empty() {
+        return error_info!("captcha_expired")
+    }
+    if answer != code.trim().to_lowercase() {
+        return error_info!("verify_code_error")
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Error;
+
+    async fn cache_with(captcha_id: &str, answer: &str) -> CacheService {
+        let cache = CacheService::new("mem").unwrap();
+        cache.set_string(&format!("{}{}", CACHE_KEY_CAPTCHA, captcha_id), answer, CAPTCHA_TTL).await.unwrap();
+        cache
+    }
+
+    fn code_of(result: Result<()>) -> String {
+        match result {
+            Err(Error::Code(code, _)) => code,
+            r => format!("{:?}", r),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_check_once() {
+        let cache = cache_with("c1", "ab3d").await;
+        assert!(check(&cache, "c1", " AB3D ").await.is_ok());
+        assert_eq!(code_of(check(&cache, "c1", "ab3d").await), "captcha_expired");
+
+        // a wrong answer uses the captcha up too
+        let cache = cache_with("c2", "ab3d").await;
+        assert_eq!(code_of(check(&cache, "c2", "xxxx").await), "verify_code_error");
+        assert_eq!(code_of(check(&cache, "c2", "ab3d").await), "captcha_expired");
+    }
+
+    #[tokio::test]
+    async fn test_check_parallel() {
+        let cache = cache_with("c1", "ab3d").await;
+        let (a, b) = tokio::join!(check(&cache, "c1", "ab3d"), check(&cache, "c1", "ab3d"));
+        assert!(a.is_ok() != b.is_ok());
+    }
+}
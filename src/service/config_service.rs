@@ -0,0 +1,318 @@
+use std::str::FromStr;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+use log::{info, warn};
+use parking_lot::RwLock;
+use rbatis::plugin::page::PageRequest;
+use rbatis::Page;
+
+use super::CONTEXT;
+use crate::{error_info, pool, Error, Result};
+use crate::config::{Config, PasswordPolicy};
+use crate::model::sys_config::*;
+use crate::vo::config_vo::*;
+
+/// Runtime knobs, the values of `application.yaml` overridden by the `sys_config` rows
+/// with the same key. Changes through the config api apply without a restart,
+/// on the other instances within `SYNC_INTERVAL` seconds.
+#[derive(Debug, Clone)]
+pub struct Settings {
+    pub login_fail_retry: u64,
+    pub login_fail_retry_wait_sec: u64,
+    pub login_lock_max_sec: u64,
+    pub login_captcha_retry: u64,
+    pub jwt_exp: u64,
+    pub jwt_refresh_token: u64,
+    pub jwt_refresh_exp: u64,
+    pub trash_recycle_days: u64,
+    /// overridden by the `password_` prefixed keys, e.g. `password_min_length`
+    pub password_policy: PasswordPolicy,
+}
+
+impl Settings {
+    fn from_config(config: &Config) -> Self {
+        Self {
+            login_fail_retry: config.login_fail_retry,
+            login_fail_retry_wait_sec: config.login_fail_retry_wait_sec,
+            login_lock_max_sec: config.login_lock_max_sec,
+            login_captcha_retry: config.login_captcha_retry,
+            jwt_exp: config.jwt_exp,
+            jwt_refresh_token: config.jwt_refresh_token,
+            jwt_refresh_exp: config.jwt_refresh_exp,
+            trash_recycle_days: config.trash_recycle_days,
+            password_policy: config.password_policy.clone(),
+        }
+    }
+
+    /// override the setting named `key`, Ok(false) when `key` is not a setting
+    fn set(&mut self, key: &str, value: &str) -> Result<bool> {
+        let Some(spec) = SETTING_SPECS.iter().find(|s| s.key == key) else {
+            return Ok(false)
+        };
+        let policy = &mut self.password_policy;
+        if spec.value_type == VALUE_TYPE_BOOL {
+            let field = match key {
+                "password_require_upper" => &mut policy.require_upper,
+                "password_require_lower" => &mut policy.require_lower,
+                "password_require_digit" => &mut policy.require_digit,
+                "password_require_special" => &mut policy.require_special,
+                _ => return Ok(false),
+            };
+            *field = parse(key, value)?;
+            return Ok(true)
+        }
+
+        let v = spec.number(value)?;
+        match key {
+            "password_min_length" => policy.min_length = v as usize,
+            "password_history" => policy.history = v,
+            "password_max_age_days" => policy.max_age_days = v,
+            "login_fail_retry" => self.login_fail_retry = v,
+            "login_fail_retry_wait_sec" => self.login_fail_retry_wait_sec = v,
+            "login_lock_max_sec" => self.login_lock_max_sec = v,
+            "login_captcha_retry" => self.login_captcha_retry = v,
+            "jwt_exp" => self.jwt_exp = v,
+            "jwt_refresh_token" => self.jwt_refresh_token = v,
+            "jwt_refresh_exp" => self.jwt_refresh_exp = v,
+            "trash_recycle_days" => self.trash_recycle_days = v,
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+}
+
+/// the type of a runtime setting, a number must be within `min..=max`
+struct SettingSpec {
+    key: &'static str,
+    value_type: &'static str,
+    min: u64,
+    max: u64,
+}
+
+const fn number(key: &'static str, min: u64, max: u64) -> SettingSpec {
+    SettingSpec { key, value_type: VALUE_TYPE_NUMBER, min, max }
+}
+
+const fn boolean(key: &'static str) -> SettingSpec {
+    SettingSpec { key, value_type: VALUE_TYPE_BOOL, min: 0, max: 0 }
+}
+
+/// the `sys_config` keys that override a setting, a value of 0 would e.g. make
+/// tokens never expire and a huge one overflows the time arithmetic
+const SETTING_SPECS: &[SettingSpec] = &[
+    number("login_fail_retry", 0, 100),
+    number("login_fail_retry_wait_sec", 1, 86400),
+    number("login_lock_max_sec", 1, 30 * 86400),
+    number("login_captcha_retry", 0, 100),
+    number("jwt_exp", 60, 86400),
+    number("jwt_refresh_token", 0, 86400),
+    number("jwt_refresh_exp", 60, 365 * 86400),
+    number("trash_recycle_days", 1, 3650),
+    number("password_min_length", 1, 128),
+    boolean("password_require_upper"),
+    boolean("password_require_lower"),
+    boolean("password_require_digit"),
+    boolean("password_require_special"),
+    number("password_history", 0, 24),
+    number("password_max_age_days", 0, 3650),
+];
+
+impl SettingSpec {
+    fn number(&self, value: &str) -> Result<u64> {
+        let v: u64 = parse(self.key, value)?;
+        if v < self.min || v > self.max {
+            return error_info!("config_value_out_of_range", format!("{} {}~{}", self.key, self.min, self.max))
+        }
+        Ok(v)
+    }
+}
+
+fn parse<T: FromStr>(key: &str, value: &str) -> Result<T> {
+    match value.trim().parse() {
+        Ok(v) => Ok(v),
+        Err(_) => error_info!("config_value_invalid", key.to_string()),
+    }
+}
+
+/// increased after every write, the other instances reload when it changes
+const CACHE_KEY_VERSION: &str = "sys_config:version";
+/// seconds between two checks of the version
+const SYNC_INTERVAL: u64 = 5;
+
+fn settings_lock() -> &'static RwLock<Arc<Settings>> {
+    static SETTINGS: OnceLock<RwLock<Arc<Settings>>> = OnceLock::new();
+    SETTINGS.get_or_init(|| RwLock::new(Arc::new(Settings::from_config(&CONTEXT.config))))
+}
+
+/// the current runtime settings
+pub fn settings() -> Arc<Settings> {
+    settings_lock().read().clone()
+}
+
+/// the version the current settings were loaded at
+fn loaded_version() -> &'static AtomicI64 {
+    static LOADED_VERSION: AtomicI64 = AtomicI64::new(0);
+    &LOADED_VERSION
+}
+
+/// the published version, 0 before the first write
+async fn version() -> Result<i64> {
+    Ok(CONTEXT.cache_service.get_string(CACHE_KEY_VERSION).await?.parse().unwrap_or_default())
+}
+
+/// rebuild the settings from `application.yaml` and the `sys_config` table,
+/// called at startup and when the version changed
+pub async fn reload() -> Result<()> {
+    // read before the rows, a write in between is loaded by the next check
+    let version = version().await.unwrap_or_default();
+    let rows = SysConfig::select_all(pool!()).await?;
+    let mut settings = Settings::from_config(&CONTEXT.config);
+    for row in rows {
+        // 无效的值跳过, 保留文件中的配置
+        if let Err(e) = settings.set(&row.config_key, &row.config_value) {
+            warn!("sys_config {} ignored: {}", row.config_key, e.msg());
+        }
+    }
+    info!("runtime settings reloaded at version {}: {:?}", version, settings);
+    *settings_lock().write() = Arc::new(settings);
+    loaded_version().store(version, Ordering::Relaxed);
+    Ok(())
+}
+
+/// publish a write to all instances, this one reloads at once
+async fn publish() -> Result<()> {
+    CONTEXT.cache_service.incr(CACHE_KEY_VERSION, 0).await?;
+    reload().await
+}
+
+/// reload the settings when another instance changed them, checked every `SYNC_INTERVAL` seconds
+pub fn spawn_sync() {
+    tokio::spawn(async {
+        let mut interval = tokio::time::interval(Duration::from_secs(SYNC_INTERVAL));
+        loop {
+            interval.tick().await;
+            let result = match version().await {
+                Ok(version) if version == loaded_version().load(Ordering::Relaxed) => continue,
+                Ok(_) => reload().await,
+                Err(e) => Err(e),
+            };
+            if let Err(e) = result {
+                warn!("sys_config sync fail: {}", e.msg());
+            }
+        }
+    });
+}
+
+// 检查参数值和类型是否匹配, 返回保存的类型.
+// 运行时配置项的类型由键名决定, 值必须在允许的范围内
+fn check_value(key: &str, value: &str, value_type: &str) -> Result<&'static str> {
+    if let Some(spec) = SETTING_SPECS.iter().find(|s| s.key == key) {
+        Settings::from_config(&CONTEXT.config).set(key, value)?;
+        return Ok(spec.value_type)
+    }
+    let (value_type, valid) = match value_type {
+        VALUE_TYPE_STRING => (VALUE_TYPE_STRING, true),
+        VALUE_TYPE_NUMBER => (VALUE_TYPE_NUMBER, value.trim().parse::<f64>().is_ok()),
+        VALUE_TYPE_BOOL => (VALUE_TYPE_BOOL, matches!(value.trim(), "true" | "false")),
+        VALUE_TYPE_JSON => (VALUE_TYPE_JSON, serde_json::from_str::<serde_json::Value>(value).is_ok()),
+        _ => return Error::err(format!("不支持的参数类型: {}", value_type)),
+    };
+    if !valid {
+        return error_info!("config_value_invalid", key.to_string());
+    }
+    Ok(value_type)
+}
+
+// 查询参数列表
+pub async fn config_list(item: ConfigListReq) -> Result<Page<ConfigListData>> {
+    info!("config_list params: {:?}", &item);
+    let rb = pool!();
+
+    let config_name = item.config_name.as_deref().unwrap_or_default();
+    let config_key = item.config_key.as_deref().unwrap_or_default();
+
+    let page_req = PageRequest::new(item.page_no, item.page_size);
+    let result = SysConfig::select_page_by_name(rb, &page_req, config_name, config_key).await?;
+
+    Ok(Page::<ConfigListData>::from(result))
+}
+
+// 添加参数
+pub async fn config_save(mut item: ConfigSaveReq) -> Result<u64> {
+    let rb = pool!();
+    item.value_type = check_value(&item.config_key, &item.config_value, &item.value_type)?.to_string();
+    if SysConfig::select_by_key(rb, &item.config_key).await?.is_some() {
+        return error_info!("config_exists")
+    }
+
+    let result = SysConfig::insert(rb, &SysConfig::from(item)).await?;
+    publish().await?;
+    Ok(result.rows_affected)
+}
+
+// 更新参数
+pub async fn config_update(mut item: ConfigUpdateReq) -> Result<u64> {
+    let rb = pool!();
+    item.value_type = check_value(&item.config_key, &item.config_value, &item.value_type)?.to_string();
+    let Some(old) = SysConfig::select_by_id(rb, item.id).await? else {
+        return Error::err("参数不存在")
+    };
+    if old.config_key != item.config_key && SysConfig::select_by_key(rb, &item.config_key).await?.is_some() {
+        return error_info!("config_exists")
+    }
+
+    let result = ConfigUpdateReq::update_by_column(rb, &item, "id").await?;
+    publish().await?;
+    Ok(result.rows_affected)
+}
+
+// 删除参数, 运行时配置项恢复为文件中的值
+pub async fn config_delete(item: ConfigDeleteReq) -> Result<u64> {
+    let rb = pool!();
+    let result = SysConfig::delete_in_column(rb, "id", &item.ids).await?;
+    publish().await?;
+    Ok(result.rows_affected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn code_of<T: std::fmt::Debug>(result: Result<T>) -> String {
+        match result {
+            Err(Error::Code(code, _)) => code,
+            r => format!("{:?}", r),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_set_range() {
+        let mut settings = Settings::from_config(&Config::default());
+        assert!(settings.set("jwt_exp", " 3600 ").unwrap());
+        assert_eq!(settings.jwt_exp, 3600);
+        assert!(settings.set("password_require_digit", "true").unwrap());
+        assert!(settings.password_policy.require_digit);
+        assert!(!settings.set("site_name", "admin").unwrap());
+
+        assert_eq!(code_of(settings.set("jwt_exp", "0")), "config_value_out_of_range");
+        assert_eq!(code_of(settings.set("jwt_refresh_exp", "0")), "config_value_out_of_range");
+        assert_eq!(code_of(settings.set("trash_recycle_days", "213503982334601")), "config_value_out_of_range");
+        assert_eq!(code_of(settings.set("trash_recycle_days", "-1")), "config_value_invalid");
+        assert_eq!(code_of(settings.set("password_require_digit", "1")), "config_value_invalid");
+        assert_eq!(settings.jwt_exp, 3600);
+    }
+
+    #[tokio::test]
+    async fn test_check_value_type() {
+        // the type of a setting comes from the key
+        assert_eq!(check_value("jwt_exp", "1800", VALUE_TYPE_STRING).unwrap(), VALUE_TYPE_NUMBER);
+        assert_eq!(check_value("password_require_upper", "false", VALUE_TYPE_JSON).unwrap(), VALUE_TYPE_BOOL);
+        assert_eq!(code_of(check_value("jwt_refresh_exp", "0", VALUE_TYPE_NUMBER)), "config_value_out_of_range");
+
+        assert_eq!(check_value("site_name", "admin", VALUE_TYPE_STRING).unwrap(), VALUE_TYPE_STRING);
+        assert_eq!(code_of(check_value("site_size", "x", VALUE_TYPE_NUMBER)), "config_value_invalid");
+        assert!(check_value("site_name", "admin", "text").is_err());
+    }
+}
@@ -0,0 +1,210 @@
+use std::future::Future;
+
+use rbatis::executor::Executor;
+use rbatis::intercept::{Intercept, ResultType};
+use rbatis::rbdc::db::ExecResult;
+use rbs::Value;
+use serde::Serialize;
+use sqlparser::ast::{BinaryOperator, Expr, SetExpr, Statement, TableFactor};
+use sqlparser::dialect::MySqlDialect;
+use sqlparser::parser::Parser;
+
+use crate::error_info;
+use crate::pool;
+use crate::{Error, Result};
+use crate::model::dept::SysDept;
+use crate::model::role::*;
+use crate::model::role_dept::SysRoleDept;
+use crate::model::user::SysUser;
+use crate::model::user_role::SysUserRole;
+
+tokio::task_local! {
+    /// the data scope of the queries run by `scoped`
+    static DATA_SCOPE: DataScope;
+}
+
+/// The users an operator can see, merged from the data scopes of all their roles.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DataScope {
+    /// no restriction
+    pub all: bool,
+    /// set when the operator can see their own record
+    pub user_id: Option<u64>,
+    /// departments whose users can be seen
+    pub dept_ids: Vec<i32>,
+}
+
+impl DataScope {
+    pub fn all() -> Self {
+        Self { all: true, ..Default::default() }
+    }
+
+    /// the where condition on the `sys_user` rows named `table`
+    fn condition(&self, table: &str) -> String {
+        let mut conditions = vec![];
+        if let Some(user_id) = self.user_id {
+            conditions.push(format!("{}.id = {}", table, user_id));
+        }
+        if !self.dept_ids.is_empty() {
+            let ids: Vec<String> = self.dept_ids.iter().map(|id| id.to_string()).collect();
+            conditions.push(format!("{}.dept_id IN ({})", table, ids.join(", ")));
+        }
+        if conditions.is_empty() {
+            return "1 = 0".to_string()
+        }
+        conditions.join(" OR ")
+    }
+
+    /// the select with the scope added to its where, `None` when it doesn't read `sys_user`
+    fn restrict(&self, sql: &str) -> std::result::Result<Option<String>, rbatis::Error> {
+        if self.all || !sql.trim_start().get(..6).is_some_and(|s| s.eq_ignore_ascii_case("select")) {
+            return Ok(None)
+        }
+        let dialect = MySqlDialect {};
+        let mut statements = Parser::parse_sql(&dialect, sql).map_err(|e| rbatis::Error::from(e.to_string()))?;
+        let [Statement::Query(query)] = statements.as_mut_slice() else {
+            return Ok(None)
+        };
+        let SetExpr::Select(select) = query.body.as_mut() else {
+            return Ok(None)
+        };
+        // the alias of sys_user when it is joined, e.g. `sys_user u`
+        let table = select.from.iter().find_map(|t| match &t.relation {
+            TableFactor::Table { name, alias, .. } if name.to_string().eq_ignore_ascii_case("sys_user") => {
+                Some(alias.as_ref().map_or_else(|| name.to_string(), |a| a.name.to_string()))
+            }
+            _ => None,
+        });
+        let Some(table) = table else {
+            return Ok(None)
+        };
+        let condition = Parser::new(&dialect)
+            .try_with_sql(&self.condition(&table))
+            .and_then(|mut p| p.parse_expr())
+            .map_err(|e| rbatis::Error::from(e.to_string()))?;
+        select.selection = Some(match select.selection.take() {
+            Some(selection) => Expr::BinaryOp {
+                left: Box::new(Expr::Nested(Box::new(selection))),
+                op: BinaryOperator::And,
+                right: Box::new(Expr::Nested(Box::new(condition))),
+            },
+            None => condition,
+        });
+        Ok(Some(statements[0].to_string()))
+    }
+}
+
+/// run the queries of `f` with the data scope of the operator,
+/// the selects from `sys_user` only return the users the operator can see
+pub async fn scoped<T, E>(operator_id: u64, f: impl Future<Output = std::result::Result<T, E>>) -> Result<T>
+where
+    Error: From<E>,
+{
+    let scope = user_scope(operator_id).await?;
+    Ok(DATA_SCOPE.scope(scope, f).await?)
+}
+
+/// adds the data scope set by `scoped` to the selects from `sys_user`
+#[derive(Debug, Default)]
+pub struct DataScopeIntercept;
+
+#[async_trait]
+impl Intercept for DataScopeIntercept {
+    async fn before(
+        &self,
+        _task_id: i64,
+        _rb: &dyn Executor,
+        sql: &mut String,
+        _args: &mut Vec<Value>,
+        _result: ResultType<&mut std::result::Result<ExecResult, rbatis::Error>, &mut std::result::Result<Vec<Value>, rbatis::Error>>,
+    ) -> std::result::Result<Option<bool>, rbatis::Error> {
+        // queries outside `scoped` are not restricted
+        if let Ok(restricted) = DATA_SCOPE.try_with(|scope| scope.restrict(sql)) {
+            if let Some(restricted) = restricted? {
+                *sql = restricted;
+            }
+        }
+        Ok(Some(true))
+    }
+}
+
+// 查询用户的数据范围, 多个角色时取并集
+pub async fn user_scope(user_id: u64) -> Result<DataScope> {
+    let rb = pool!();
+    // 超级管理员可以查看全部数据
+    if !SysUserRole::is_admin(rb, user_id).await?.is_empty() {
+        return Ok(DataScope::all())
+    }
+    let Some(user) = SysUser::select_by_id(rb, user_id).await? else {
+        return error_info!("user_not_exists")
+    };
+
+    let mut scope = DataScope::default();
+    for role in SysRole::select_by_user(rb, user_id).await? {
+        match role.data_scope {
+            DATA_SCOPE_ALL => return Ok(DataScope::all()),
+            DATA_SCOPE_CUSTOM => {
+                let depts = SysRoleDept::select_by_column(rb, "role_id", role.id).await?;
+                scope.dept_ids.extend(depts.into_iter().map(|x| x.dept_id));
+            }
+            DATA_SCOPE_DEPT => scope.dept_ids.extend(user.dept_id),
+            DATA_SCOPE_DEPT_AND_CHILD => {
+                if let Some(dept_id) = user.dept_id {
+                    let depts = SysDept::select_subtree(rb, dept_id).await?;
+                    scope.dept_ids.extend(depts.into_iter().filter_map(|x| x.id));
+                }
+            }
+            // 仅本人, 未知的范围按最小权限处理
+            _ => scope.user_id = user.id,
+        }
+    }
+    scope.dept_ids.sort_unstable();
+    scope.dept_ids.dedup();
+    Ok(scope)
+}
+
+// 操作的用户都必须在数据范围内
+pub async fn check_users(operator_id: u64, ids: &[u64]) -> Result<()> {
+    if ids.is_empty() {
+        return Ok(())
+    }
+    let rb = pool!();
+    let visible = scoped(operator_id, SysUser::select_in_column(rb, "id", ids)).await?;
+    let existing = SysUser::select_in_column(rb, "id", ids).await?;
+    if visible.len() == existing.len() {
+        Ok(())
+    } else {
+        error_info!("data_scope_denied")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_restrict() {
+        let scope = DataScope { all: false, user_id: Some(7), dept_ids: vec![1, 3] };
+        assert_eq!(
+            scope.restrict("select * from sys_user where mobile = ? order by create_time desc limit 0,10").unwrap().as_deref(),
+            Some("SELECT * FROM sys_user WHERE (mobile = ?) AND (sys_user.id = 7 OR sys_user.dept_id IN (1, 3)) ORDER BY create_time DESC LIMIT 10 OFFSET 0")
+        );
+        assert_eq!(
+            scope.restrict("select count(1) as count from sys_user").unwrap().as_deref(),
+            Some("SELECT count(1) AS count FROM sys_user WHERE sys_user.id = 7 OR sys_user.dept_id IN (1, 3)")
+        );
+        assert_eq!(
+            scope.restrict("select u.* from sys_user u left join sys_dept d on u.dept_id = d.id where d.status_id = 1").unwrap().as_deref(),
+            Some("SELECT u.* FROM sys_user AS u LEFT JOIN sys_dept AS d ON u.dept_id = d.id WHERE (d.status_id = 1) AND (u.id = 7 OR u.dept_id IN (1, 3))")
+        );
+    }
+
+    #[test]
+    fn test_restrict_untouched() {
+        let scope = DataScope { all: false, user_id: None, dept_ids: vec![] };
+        assert_eq!(scope.restrict("select * from sys_role").unwrap(), None);
+        assert_eq!(scope.restrict("delete from sys_user where id = ?").unwrap(), None);
+        assert_eq!(scope.restrict("select * from sys_user").unwrap().as_deref(), Some("SELECT * FROM sys_user WHERE 1 = 0"));
+        assert_eq!(DataScope::all().restrict("select * from sys_user").unwrap(), None);
+    }
+}
@@ -0,0 +1,91 @@
+use log::info;
+
+use crate::Error;
+use crate::Result;
+use crate::pool;
+use crate::model::dept::SysDept;
+use crate::model::user::SysUser;
+use crate::vo::dept_vo::*;
+
+
+pub async fn dept_list(item: DeptListReq) -> Result<Vec<DeptListData>> {
+    let rb = pool!();
+    // 部门是树形结构不需要分页
+    let result = match item.dept_name.as_deref().map(str::trim).filter(|n| !n.is_empty()) {
+        Some(dept_name) => SysDept::select_by_name(rb, dept_name).await?,
+        None => SysDept::select_all(rb).await?,
+    };
+    let dept_list: Vec<DeptListData> = result.into_iter().map(DeptListData::from).collect();
+    Ok(dept_list)
+    
+}
+
+// 上级部门的ancestors加上上级部门的id
+async fn ancestors_of(parent_id: i32) -> Result<String> {
+    if parent_id == 0 {
+        return Ok("0".to_string())
+    }
+    match SysDept::select_by_id(pool!(), parent_id).await? {
+        Some(parent) => Ok(format!("{},{}", parent.ancestors, parent_id)),
+        None => Error::err("上级部门不存在"),
+    }
+}
+
+// 添加部门
+pub async fn dept_save(item: DeptSaveReq) -> Result<u64> {
+    let rb = pool!();
+
+    let mut sys_dept = SysDept::from(item);
+    sys_dept.ancestors = ancestors_of(sys_dept.parent_id).await?;
+
+    let result = SysDept::insert(rb, &sys_dept).await?;
+
+    Ok(result.rows_affected)
+}
+
+// 更新部门, 上级部门改变时同时更新下级部门的ancestors
+pub async fn dept_update(item: DeptUpdateReq) -> Result<u64> {
+    info!("dept_update params: {:?}", &item);
+    let rb = pool!();
+    let Some(old) = SysDept::select_by_id(rb, item.id).await? else {
+        return Error::err("部门不存在")
+    };
+
+    let mut sys_dept = SysDept::from(item);
+    sys_dept.ancestors = old.ancestors.clone();
+    if sys_dept.parent_id != old.parent_id {
+        let ancestors = ancestors_of(sys_dept.parent_id).await?;
+        let id = old.id.unwrap();
+        if sys_dept.parent_id == id || ancestors.split(',').any(|a| a == id.to_string()) {
+            return Error::err("上级部门不能是自己或下级部门")
+        }
+        sys_dept.ancestors = ancestors;
+        let old_prefix = format!("{},{}", old.ancestors, id);
+        let new_prefix = format!("{},{}", sys_dept.ancestors, id);
+        SysDept::update_children_ancestors(rb, id, &old_prefix, &new_prefix).await?;
+    }
+    let result = SysDept::update_by_column(rb, &sys_dept, "id").await?;
+
+    Ok(result.rows_affected)
+}
+
+// 删除部门信息
+pub async fn dept_delete(item: DeptDeleteReq) -> Result<u64> {
+    info!("dept_delete params: {:?}", &item);
+    let rb = pool!();
+    let mut count = 0;
+    for id in item.ids {
+        //有下级或者有用户的时候 不能直接删除
+        let depts = SysDept::select_by_column(rb, "parent_id", &id).await?;
+        if !depts.is_empty() {
+            return Error::err("有下级部门,不能直接删除")
+        }
+        let users = SysUser::select_by_column(rb, "dept_id", &id).await?;
+        if !users.is_empty() {
+            return Error::err("部门下有用户,不能直接删除")
+        }
+        let result = SysDept::delete_by_column(rb, "id", &id).await?;
+        count += result.rows_affected;
+    }
+    Ok(count)
+}
\ No newline at end of file
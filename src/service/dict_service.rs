@@ -0,0 +1,214 @@
+use std::collections::HashMap;
+
+use rbatis::plugin::page::PageRequest;
+use rbatis::Page;
+use log::info;
+
+use crate::{error_info, pool, Error, Result};
+use crate::model::dict_data::SysDictData;
+use crate::model::dict_type::SysDictType;
+use crate::service::CONTEXT;
+use crate::vo::dict_vo::*;
+
+const CACHE_KEY_DICT: &str = "dict:";
+/// names of all dict types, unknown types are rejected without a query
+const CACHE_KEY_DICT_TYPES: &str = "dict_types";
+// 缓存在写入时清除, 过期时间只是兜底
+const DICT_CACHE_TTL: u64 = 3600;
+/// max dict types of a lookup
+const MAX_LOOKUP_TYPES: usize = 20;
+
+// 查询字典类型列表
+pub async fn dict_type_list(item: DictTypeListReq) -> Result<Page<DictTypeListData>> {
+    info!("dict_type_list params: {:?}", &item);
+    let rb = pool!();
+
+    let dict_name = item.dict_name.as_deref().unwrap_or_default();
+    let dict_type = item.dict_type.as_deref().unwrap_or_default();
+    let status_id = item.status_id.as_deref().unwrap_or_default();
+
+    let page_req = PageRequest::new(item.page_no, item.page_size);
+    let result = SysDictType::select_page_by_name(rb, &page_req, dict_name, dict_type, status_id).await?;
+
+    Ok(Page::<DictTypeListData>::from(result))
+}
+
+// 添加字典类型
+pub async fn dict_type_save(item: DictTypeSaveReq) -> Result<u64> {
+    let rb = pool!();
+    if SysDictType::select_by_type(rb, &item.dict_type).await?.is_some() {
+        return error_info!("dict_exists")
+    }
+
+    let result = SysDictType::insert(rb, &SysDictType::from(item)).await?;
+    evict_types().await?;
+    Ok(result.rows_affected)
+}
+
+// 更新字典类型, 编码修改时同步修改字典数据
+pub async fn dict_type_update(item: DictTypeUpdateReq) -> Result<u64> {
+    let rb = pool!();
+    let Some(old) = SysDictType::select_by_id(rb, item.id).await? else {
+        return Error::err("字典类型不存在")
+    };
+
+    if old.dict_type != item.dict_type {
+        if SysDictType::select_by_type(rb, &item.dict_type).await?.is_some() {
+            return error_info!("dict_exists")
+        }
+        SysDictData::update_type(rb, &old.dict_type, &item.dict_type).await?;
+        evict(&item.dict_type).await?;
+        evict_types().await?;
+    }
+    let result = DictTypeUpdateReq::update_by_column(rb, &item, "id").await?;
+    evict(&old.dict_type).await?;
+    Ok(result.rows_affected)
+}
+
+// 删除字典类型和它的字典数据
+pub async fn dict_type_delete(item: DictTypeDeleteReq) -> Result<u64> {
+    let rb = pool!();
+    let dict_types = SysDictType::select_in_column(rb, "id", &item.ids).await?;
+    for dict in &dict_types {
+        SysDictData::delete_by_column(rb, "dict_type", &dict.dict_type).await?;
+        evict(&dict.dict_type).await?;
+    }
+    let result = SysDictType::delete_in_column(rb, "id", &item.ids).await?;
+    evict_types().await?;
+    Ok(result.rows_affected)
+}
+
+// 查询字典数据列表
+pub async fn dict_data_list(item: DictDataListReq) -> Result<Page<DictDataListData>> {
+    info!("dict_data_list params: {:?}", &item);
+    let rb = pool!();
+
+    let dict_label = item.dict_label.as_deref().unwrap_or_default();
+    let status_id = item.status_id.as_deref().unwrap_or_default();
+
+    let page_req = PageRequest::new(item.page_no, item.page_size);
+    let result = SysDictData::select_page_by_type(rb, &page_req, &item.dict_type, dict_label, status_id).await?;
+
+    Ok(Page::<DictDataListData>::from(result))
+}
+
+// 添加字典数据
+pub async fn dict_data_save(item: DictDataSaveReq) -> Result<u64> {
+    let rb = pool!();
+    if SysDictType::select_by_type(rb, &item.dict_type).await?.is_none() {
+        return Error::err("字典类型不存在")
+    }
+    let values = SysDictData::select_by_column(rb, "dict_type", &item.dict_type).await?;
+    if values.iter().any(|x| x.dict_value == item.dict_value) {
+        return Error::err("字典值已存在")
+    }
+
+    let dict_type = item.dict_type.clone();
+    let result = SysDictData::insert(rb, &SysDictData::from(item)).await?;
+    evict(&dict_type).await?;
+    Ok(result.rows_affected)
+}
+
+// 更新字典数据, 可以移动到其他字典类型
+pub async fn dict_data_update(mut item: DictDataUpdateReq) -> Result<u64> {
+    let rb = pool!();
+    let Some(old) = SysDictData::select_by_id(rb, item.id).await? else {
+        return Error::err("字典数据不存在")
+    };
+    let dict_type = item.dict_type.clone().unwrap_or_else(|| old.dict_type.clone());
+    let moved = dict_type != old.dict_type;
+    if moved && SysDictType::select_by_type(rb, &dict_type).await?.is_none() {
+        return Error::err("字典类型不存在")
+    }
+    if moved || old.dict_value != item.dict_value {
+        let values = SysDictData::select_by_column(rb, "dict_type", &dict_type).await?;
+        if values.iter().any(|x| x.id != old.id && x.dict_value == item.dict_value) {
+            return Error::err("字典值已存在")
+        }
+    }
+
+    item.dict_type = Some(dict_type.clone());
+    let result = DictDataUpdateReq::update_by_column(rb, &item, "id").await?;
+    evict(&old.dict_type).await?;
+    if moved {
+        evict(&dict_type).await?;
+    }
+    Ok(result.rows_affected)
+}
+
+// 删除字典数据
+pub async fn dict_data_delete(item: DictDataDeleteReq) -> Result<u64> {
+    let rb = pool!();
+    let data = SysDictData::select_in_column(rb, "id", &item.ids).await?;
+    let result = SysDictData::delete_in_column(rb, "id", &item.ids).await?;
+
+    let mut dict_types: Vec<&str> = data.iter().map(|x| x.dict_type.as_str()).collect();
+    dict_types.sort_unstable();
+    dict_types.dedup();
+    for dict_type in dict_types {
+        evict(dict_type).await?;
+    }
+    Ok(result.rows_affected)
+}
+
+// 查询多个字典类型的数据, 前端用来显示标签
+pub async fn dict_lookup(item: DictLookupReq) -> Result<HashMap<String, Vec<DictItem>>> {
+    if item.dict_types.len() > MAX_LOOKUP_TYPES {
+        return error_info!("dict_lookup_too_many", MAX_LOOKUP_TYPES.to_string())
+    }
+    // 匿名接口, 不存在的类型直接拒绝, 不查询数据库
+    let names = dict_type_names().await?;
+    if let Some(unknown) = item.dict_types.iter().find(|t| !names.contains(t)) {
+        return error_info!("dict_type_unknown", unknown.clone())
+    }
+    let mut result = HashMap::with_capacity(item.dict_types.len());
+    for dict_type in item.dict_types {
+        let items = dict_items(&dict_type).await?;
+        result.insert(dict_type, items);
+    }
+    Ok(result)
+}
+
+/// enabled items of the dict type, read through the cache.
+/// A disabled or missing dict type has no items
+pub async fn dict_items(dict_type: &str) -> Result<Vec<DictItem>> {
+    let key = format!("{}{}", CACHE_KEY_DICT, dict_type);
+    if let Some(items) = CONTEXT.cache_service.get_json::<Option<Vec<DictItem>>>(&key).await? {
+        return Ok(items)
+    }
+
+    let rb = pool!();
+    // 不缓存不存在的类型, 匿名请求不能随意写入缓存
+    let Some(dict) = SysDictType::select_by_type(rb, dict_type).await? else {
+        return Ok(Vec::new())
+    };
+    let items: Vec<DictItem> = if dict.status_id == 1 {
+        SysDictData::select_enabled(rb, dict_type).await?
+            .into_iter().map(DictItem::from).collect()
+    } else {
+        Vec::new()
+    };
+    CONTEXT.cache_service.set_json(&key, &items, DICT_CACHE_TTL).await?;
+    Ok(items)
+}
+
+/// the names of all dict types, read through the cache
+async fn dict_type_names() -> Result<Vec<String>> {
+    if let Some(names) = CONTEXT.cache_service.get_json::<Option<Vec<String>>>(CACHE_KEY_DICT_TYPES).await? {
+        return Ok(names)
+    }
+    let names: Vec<String> = SysDictType::select_all(pool!()).await?
+        .into_iter().map(|x| x.dict_type).collect();
+    CONTEXT.cache_service.set_json(CACHE_KEY_DICT_TYPES, &names, DICT_CACHE_TTL).await?;
+    Ok(names)
+}
+
+async fn evict_types() -> Result<()> {
+    CONTEXT.cache_service.remove(CACHE_KEY_DICT_TYPES).await?;
+    Ok(())
+}
+
+async fn evict(dict_type: &str) -> Result<()> {
+    CONTEXT.cache_service.remove(&format!("{}{}", CACHE_KEY_DICT, dict_type)).await?;
+    Ok(())
+}
@@ -0,0 +1,65 @@
+use rbatis::plugin::page::PageRequest;
+use rbatis::rbdc::datetime::DateTime;
+use rbatis::Page;
+use log::warn;
+
+use crate::{pool, Error, Result};
+use crate::middleware::context::ClientInfo;
+use crate::model::login_log::*;
+use crate::model::user::SysUser;
+use crate::vo::login_log_vo::*;
+use crate::vo::user_vo::UserLoginData;
+
+/// persist a login attempt in the background, a successful one also updates the user's last login.
+/// `user_id` is the account the attempt was for when it is known.
+/// Failures are only logged so they never fail the login itself
+pub fn record(login_type: &str, account: &str, user_id: Option<u64>, client: &ClientInfo, result: &Result<UserLoginData>) {
+    let (status, reason, msg) = match result {
+        Ok(data) if data.challenge_token.is_some() => (LOGIN_STATUS_CHALLENGE, LOGIN_REASON_CHALLENGE.to_string(), "等待两步验证".to_string()),
+        Ok(_) => (LOGIN_STATUS_SUCCESS, LOGIN_REASON_SUCCESS.to_string(), String::new()),
+        Err(Error::Code(code, msg)) => (LOGIN_STATUS_FAIL, code.clone(), msg.clone()),
+        Err(e) => (LOGIN_STATUS_FAIL, LOGIN_REASON_ERROR.to_string(), e.msg()),
+    };
+    let log = SysLoginLog {
+        id: None,
+        create_time: Some(DateTime::now()),
+        user_id,
+        account: account.to_string(),
+        login_type: login_type.to_string(),
+        status,
+        reason,
+        msg,
+        ip: client.ip.clone(),
+        user_agent: client.user_agent.clone(),
+    };
+    tokio::spawn(async move {
+        if let Err(e) = save(&log).await {
+            warn!("save login log of {} err: {}", log.account, e.msg());
+        }
+    });
+}
+
+async fn save(log: &SysLoginLog) -> Result<()> {
+    let rb = pool!();
+    SysLoginLog::insert(rb, log).await?;
+
+    if let (LOGIN_STATUS_SUCCESS, Some(id), Some(now)) = (log.status, log.user_id, &log.create_time) {
+        SysUser::update_last_login(rb, id, now.clone(), &log.ip).await?;
+    }
+    Ok(())
+}
+
+// 查询登录日志
+pub async fn login_log_list(item: LoginLogListReq) -> Result<Page<LoginLogListData>> {
+    let page_req = PageRequest::new(item.page_no, item.page_size);
+    let result = SysLoginLog::select_page_by_filter(
+        pool!(),
+        &page_req,
+        item.account.as_deref().unwrap_or_default(),
+        item.status,
+        item.begin_time.as_deref().unwrap_or_default(),
+        item.end_time.as_deref().unwrap_or_default(),
+    ).await?;
+
+    Ok(Page::<LoginLogListData>::from(result))
+}
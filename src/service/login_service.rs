@@ -1,28 +1,37 @@
+use log::info;
+use rbatis::rbdc::datetime::DateTime;
 
 use super::CONTEXT;
-use crate::{error::Result, Error};
+use super::config_service::{self, Settings};
+use crate::model::user::SysUser;
+use crate::utils::cache::cache_service::CacheService;
+use crate::utils::get_timestamp;
+use crate::{error::Result, error_info, pool};
 
 const CACHE_KEY_RETRY: &str = "login:login_retry";
 const CACHE_KEY_RETRY_TTL: &str = "login:login_retry_ttl";
+/// failures are counted for at least 15 minutes
+const RETRY_WINDOW_SEC: u64 = 60 * 15;
 
 ///is need to wait
 pub async fn is_need_wait_login_ex(account: &str) -> Result<u64> {
-    if CONTEXT.config.login_fail_retry > 0 {
-        let num: Option<u64> = CONTEXT
-            .mem_cache_service
-            .get_json(&format!("{}{}", CACHE_KEY_RETRY, account))
-            .await?;
-        let num = num.unwrap_or(0);
+    wait_login(&CONTEXT.cache_service, &config_service::settings(), account).await
+}
+
+async fn wait_login(cache: &CacheService, settings: &Settings, account: &str) -> Result<u64> {
+    if settings.login_fail_retry > 0 {
+        let num: u64 = cache
+            .get_string(&format!("{}{}", CACHE_KEY_RETRY, account))
+            .await?
+            .parse()
+            .unwrap_or(0);
 
-        if num >= CONTEXT.config.login_fail_retry {
-            let wait_sec: i64 = CONTEXT
-                .mem_cache_service
+        if num >= settings.login_fail_retry {
+            let wait_sec: i64 = cache
                 .ttl(&format!("{}{}", CACHE_KEY_RETRY_TTL, account))
                 .await.unwrap_or_default();
             if wait_sec > 0 {
-                // let e = error_info!("req_frequently", format!("{}", wait_sec));
-                let e = Error::E(format!("操作过于频繁请等待{}秒后重试", wait_sec));
-                return Err(e);
+                return error_info!("req_frequently", wait_sec.to_string());
             }
         }
         return Ok(num);
@@ -30,44 +39,124 @@ pub async fn is_need_wait_login_ex(account: &str) -> Result<u64> {
     Ok(0)
 }
 
-///Add redis retry record
-pub async fn add_retry_login_limit_num( account: &str) -> Result<()> {
-    if CONTEXT.config.login_fail_retry > 0 {
-        let num: Option<u64> = CONTEXT
-            .mem_cache_service
-            .get_json(&format!("{}{}", CACHE_KEY_RETRY, account))
-            .await?;
-        let mut num = num.unwrap_or(0);
+///is the captcha required after `try_num` failed logins
+pub fn is_need_captcha(try_num: u64) -> bool {
+    try_num >= config_service::settings().login_captcha_retry
+}
+
+/// the account is locked in the database, survives restarts and is shared by instances
+pub fn check_locked(user: &SysUser) -> Result<()> {
+    let now = get_timestamp() as i64;
+    if let Some(locked_until) = user.locked_until.as_ref().map(|t| t.unix_timestamp()) {
+        if locked_until > now {
+            return error_info!("account_locked", (locked_until - now).to_string());
+        }
+    }
+    Ok(())
+}
 
-        num += 1;
+/// seconds to wait after `num` failures, doubled for each failure past `login_fail_retry`
+fn backoff_sec(num: u64, config: &Settings) -> u64 {
+    let exp = num.saturating_sub(config.login_fail_retry).min(20) as u32;
+    config.login_fail_retry_wait_sec.saturating_mul(1 << exp).min(config.login_lock_max_sec)
+}
+
+///Add retry record, the account is locked with progressive backoff once `login_fail_retry` is reached
+pub async fn add_retry_login_limit_num(account: &str) -> Result<()> {
+    let config = config_service::settings();
+    let Some((num, wait_sec)) = count_failure(&CONTEXT.cache_service, &config, account).await? else {
+        return Ok(());
+    };
+
+    let rb = pool!();
+    if let Some(user) = SysUser::select_by_mobile(rb, account).await? {
+        let locked_until = DateTime::from_timestamp((get_timestamp() + wait_sec) as i64);
+        let reason = format!("连续登录失败{}次", num);
+        SysUser::update_lock(rb, user.id.unwrap_or_default(), Some(locked_until), Some(&reason)).await?;
+        info!("lock account: {}, {} seconds", account, wait_sec);
+    }
+    Ok(())
+}
+
+/// count the failure, return the count and the seconds to wait once the account is locked.
+/// the counter expires with the window, set in the same step as the increase
+async fn count_failure(cache: &CacheService, config: &Settings, account: &str) -> Result<Option<(u64, u64)>> {
+    if config.login_fail_retry == 0 {
+        return Ok(None);
+    }
+    let window = config.login_lock_max_sec.max(RETRY_WINDOW_SEC);
+    let num = cache.incr(&format!("{}{}", CACHE_KEY_RETRY, account), window).await? as u64;
+    if num < config.login_fail_retry {
+        return Ok(None);
+    }
+
+    let wait_sec = backoff_sec(num, config);
+    cache
+        .set_string(&format!("{}{}", CACHE_KEY_RETRY_TTL, account), &num.to_string(), wait_sec)
+        .await?;
+    Ok(Some((num, wait_sec)))
+}
+
+pub async fn remove_retry_login_limit_num(account: &str) -> Result<()> {
+    if config_service::settings().login_fail_retry > 0 {
         CONTEXT
-            .mem_cache_service
-            .set_string(
-                &format!("{}{}", CACHE_KEY_RETRY, account),
-                &num.to_string(),
-                60*15,
-            )
+            .cache_service
+            .remove(&format!("{}{}", CACHE_KEY_RETRY, account))
             .await?;
         CONTEXT
-            .mem_cache_service
-            .set_string(
-                &format!("{}{}", CACHE_KEY_RETRY_TTL, account),
-                &num.to_string(),
-                CONTEXT.config.login_fail_retry_wait_sec
-            )
+            .cache_service
+            .remove(&format!("{}{}", CACHE_KEY_RETRY_TTL, account))
             .await?;
     }
     Ok(())
 }
 
-pub async fn remove_retry_login_limit_num(account: &str) -> Result<()> {
-    if CONTEXT.config.login_fail_retry > 0 {
-        CONTEXT
-            .mem_cache_service
-            .remove(
-                &format!("{}{}", CACHE_KEY_RETRY, account),
-            )
-            .await?;
+/// clear the lock and the failure counter of the user
+pub async fn unlock(user: &SysUser) -> Result<()> {
+    remove_retry_login_limit_num(&user.mobile).await?;
+    if user.locked_until.is_some() || user.lock_reason.is_some() {
+        SysUser::update_lock(pool!(), user.id.unwrap_or_default(), None, None).await?;
     }
     Ok(())
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings() -> Settings {
+        let mut settings = (*config_service::settings()).clone();
+        settings.login_fail_retry = 3;
+        settings.login_fail_retry_wait_sec = 2;
+        settings.login_lock_max_sec = 2;
+        settings
+    }
+
+    #[tokio::test]
+    async fn test_lockout_expiry() {
+        let cache = CacheService::new("mem").unwrap();
+        let settings = settings();
+        for _ in 1..3 {
+            assert_eq!(count_failure(&cache, &settings, "13800000000").await.unwrap(), None);
+        }
+        assert_eq!(wait_login(&cache, &settings, "13800000000").await.unwrap(), 2);
+        assert_eq!(count_failure(&cache, &settings, "13800000000").await.unwrap(), Some((3, 2)));
+        assert!(wait_login(&cache, &settings, "13800000000").await.is_err());
+
+        // both the lock and the failure counter expire
+        let ttl = cache.ttl(&format!("{}{}", CACHE_KEY_RETRY, "13800000000")).await.unwrap();
+        assert!(ttl > 0 && ttl <= RETRY_WINDOW_SEC as i64);
+        tokio::time::sleep(std::time::Duration::from_millis(2100)).await;
+        assert_eq!(wait_login(&cache, &settings, "13800000000").await.unwrap(), 3);
+    }
+
+    #[test]
+    fn test_backoff_sec() {
+        let mut settings = settings();
+        settings.login_fail_retry_wait_sec = 60;
+        settings.login_lock_max_sec = 3600;
+        assert_eq!(backoff_sec(3, &settings), 60);
+        assert_eq!(backoff_sec(4, &settings), 120);
+        assert_eq!(backoff_sec(100, &settings), 3600);
+    }
+}
@@ -4,6 +4,7 @@ use crate::Error;
 use crate::Result;
 use crate::pool;
 use crate::model::menu::SysMenu;
+use crate::service::token_service;
 use crate::vo::menu_vo::*;
 
 
@@ -28,13 +29,19 @@ pub async fn menu_save(item: MenuSaveReq) -> Result<u64> {
     Ok(result.rows_affected)
 }
 
-// 更新菜单
+// 更新菜单, 接口地址或状态变化时所有用户需要重新获取权限
 pub async fn menu_update(item: MenuUpdateReq) -> Result<u64> {
     info!("menu_update params: {:?}", &item);
     let rb = pool!();
+    let Some(old) = SysMenu::select_by_id(rb, item.id).await? else {
+        return Error::err("菜单不存在")
+    };
     let sys_menu = SysMenu::from(item);
     let result = SysMenu::update_by_column(rb, &sys_menu, "id").await?;
 
+    if old.api_url != sys_menu.api_url || old.status_id != sys_menu.status_id {
+        token_service::bump_all_perm_version().await?;
+    }
     Ok(result.rows_affected)
 }
 
@@ -52,5 +59,8 @@ pub async fn menu_delete(item: MenuDeleteReq) -> Result<u64> {
         let result = SysMenu::delete_by_column(rb, "id", &id).await?;
         count += result.rows_affected;
     }
+    if count > 0 {
+        token_service::bump_all_perm_version().await?;
+    }
     Ok(count)
 }
\ No newline at end of file
@@ -2,70 +2,49 @@ use once_cell::sync::Lazy;
 use rbatis::RBatis;
 
 use crate::config::Config;
-<<<<<<< HEAD
-use crate::config::db::init_db;
-=======
 use crate::utils::db::init_db;
 use crate::utils::cache::cache_service::CacheService;
->>>>>>> dev
 
 pub mod sys_trash_service;
 pub mod menu_service;
 pub mod user_service;
 pub mod role_service;
+pub mod token_service;
+pub mod session_service;
+pub mod api_key_service;
+pub mod captcha_service;
+pub mod sms_service;
+pub mod password_service;
+pub mod oidc_service;
+pub mod authenticator;
+pub mod dept_service;
+pub mod data_scope_service;
+pub mod post_service;
+pub mod dict_service;
+pub mod config_service;
+pub mod oper_log_service;
+pub mod login_log_service;
+pub mod totp_service;
 
-<<<<<<< HEAD
-=======
 pub mod login_service;
 
->>>>>>> dev
 /// CONTEXT is all the service struct
 pub static CONTEXT: Lazy<ServiceContext> = Lazy::new(||{
     ServiceContext::new()
 });
 
-<<<<<<< HEAD
-/// erverwhere use the `context!` macro to get a reference to the `ServiceContext` struct.
-#[macro_export]
-macro_rules! context {
-    () => {
-        &$crate::service::CONTEXT
-    };
-}
-
-/// erverwhere use the `pool!` macro to get a reference to the `RBatis` pool.
-#[macro_export]
-macro_rules! pool {
-    () => {
-        &$crate::service::CONTEXT.rb
-    };
-}
-
-=======
->>>>>>> dev
 #[derive(Default)]
 pub struct ServiceContext {
     pub config: Config,
     pub rb: RBatis,
-<<<<<<< HEAD
-    // pub cache_service: CacheService,
-=======
     pub cache_service: CacheService,
     pub mem_cache_service: CacheService,
->>>>>>> dev
 }
 
 impl ServiceContext {
 
     pub fn new() -> Self {
         let config = Config::new();
-<<<<<<< HEAD
-        let rb = RBatis::new();
-
-        Self {
-            config,
-            rb,
-=======
         let cache_service = CacheService::new(&config.cache_type).unwrap();
         let mem_cache_service = CacheService::new("mem").unwrap();
         
@@ -74,7 +53,6 @@ impl ServiceContext {
             rb: RBatis::new(),
             cache_service,
             mem_cache_service,
->>>>>>> dev
         }
     }
 
@@ -0,0 +1,456 @@
+use std::collections::HashMap;
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use jsonwebtoken::jwk::JwkSet;
+use jsonwebtoken::{decode, decode_header, DecodingKey, Validation};
+use log::info;
+use rbatis::rbdc::datetime::DateTime;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::sync::{OnceCell, RwLock};
+
+use super::CONTEXT;
+use crate::config::OidcConfig;
+use crate::error::{Error, Result};
+use crate::model::user::{SysUser, AUTH_SOURCE_OIDC};
+use crate::model::user_oidc::SysUserOidc;
+use crate::pool;
+use crate::service::user_service;
+use crate::utils::cache::cache_service::CacheService;
+use crate::utils::{random_string, url_encode};
+use crate::vo::oidc_vo::*;
+
+const CACHE_KEY_STATE: &str = "oidc:state:";
+/// seconds the user has to finish the login at the identity provider
+const STATE_TTL: u64 = 600;
+
+/// the provider metadata from `/.well-known/openid-configuration`
+#[derive(Debug, Deserialize)]
+struct Discovery {
+    issuer: String,
+    authorization_endpoint: String,
+    token_endpoint: String,
+    jwks_uri: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct AuthState {
+    nonce: String,
+    code_verifier: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    id_token: String,
+}
+
+/// claims of the id token, the rest are kept for the mobile, name and group claims
+#[derive(Debug, Deserialize)]
+pub struct IdTokenClaims {
+    pub sub: String,
+    pub nonce: Option<String>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+impl IdTokenClaims {
+    fn string(&self, claim: &str) -> Option<String> {
+        self.extra.get(claim).and_then(|v| v.as_str()).map(String::from)
+    }
+
+    /// `phone_number_verified`, a missing claim is not verified
+    fn is_phone_verified(&self) -> bool {
+        self.extra.get("phone_number_verified").and_then(|v| v.as_bool()).unwrap_or(false)
+    }
+
+    /// a group claim may be a string or an array of strings
+    fn groups(&self, claim: &str) -> Vec<String> {
+        match self.extra.get(claim) {
+            Some(serde_json::Value::Array(values)) => values.iter().filter_map(|v| v.as_str()).map(String::from).collect(),
+            Some(serde_json::Value::String(s)) => vec![s.clone()],
+            _ => vec![],
+        }
+    }
+}
+
+fn config() -> Result<&'static OidcConfig> {
+    match &CONTEXT.config.oidc {
+        Some(oidc) => Ok(oidc),
+        None => Error::err("未配置单点登录"),
+    }
+}
+
+fn http() -> &'static reqwest::Client {
+    static CLIENT: std::sync::OnceLock<reqwest::Client> = std::sync::OnceLock::new();
+    CLIENT.get_or_init(reqwest::Client::new)
+}
+
+fn http_err(e: reqwest::Error) -> Error {
+    info!("oidc request err: {:?}", e);
+    Error::E("单点登录服务请求失败".to_string())
+}
+
+/// the identity provider of `oidc`, discovered on first use
+async fn provider() -> Result<&'static Provider> {
+    static PROVIDER: OnceCell<Provider> = OnceCell::const_new();
+    PROVIDER
+        .get_or_try_init(|| async { Provider::discover(config()?, &CONTEXT.cache_service).await })
+        .await
+}
+
+/// an identity provider with its metadata and keys, the login states are kept in `cache`
+struct Provider {
+    config: &'static OidcConfig,
+    discovery: Discovery,
+    cache: &'static CacheService,
+    jwks: RwLock<Option<JwkSet>>,
+}
+
+impl Provider {
+    async fn discover(config: &'static OidcConfig, cache: &'static CacheService) -> Result<Self> {
+        let url = format!("{}/.well-known/openid-configuration", config.issuer.trim_end_matches('/'));
+        let discovery = http().get(url).send().await.map_err(http_err)?.json::<Discovery>().await.map_err(http_err)?;
+        Ok(Self { config, discovery, cache, jwks: RwLock::const_new(None) })
+    }
+
+    /// the provider keys, fetched again when a token is signed by an unknown kid
+    async fn decoding_key(&self, kid: Option<&str>) -> Result<DecodingKey> {
+        for refresh in [false, true] {
+            if refresh || self.jwks.read().await.is_none() {
+                let jwks = http().get(&self.discovery.jwks_uri).send().await.map_err(http_err)?
+                    .json::<JwkSet>().await.map_err(http_err)?;
+                *self.jwks.write().await = Some(jwks);
+            }
+            let guard = self.jwks.read().await;
+            let jwk = guard.as_ref().and_then(|set| match kid {
+                Some(kid) => set.find(kid),
+                None => set.keys.first(),
+            });
+            if let Some(jwk) = jwk {
+                return Ok(DecodingKey::from_jwk(jwk)?);
+            }
+        }
+        Error::err("单点登录令牌签名密钥不存在")
+    }
+
+    async fn authorize(&self) -> Result<OidcAuthorizeData> {
+        let config = self.config;
+        let state = random_string(32);
+        let auth_state = AuthState {
+            nonce: random_string(32),
+            code_verifier: random_string(64),
+        };
+        let code_challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(auth_state.code_verifier.as_bytes()));
+        self.cache
+            .set_json(&format!("{}{}", CACHE_KEY_STATE, state), &auth_state, STATE_TTL)
+            .await?;
+
+        let authorize_url = format!(
+            "{}?response_type=code&client_id={}&redirect_uri={}&scope={}&state={}&nonce={}&code_challenge={}&code_challenge_method=S256",
+            self.discovery.authorization_endpoint,
+            url_encode(&config.client_id),
+            url_encode(&config.redirect_uri),
+            url_encode(&config.scopes.join(" ")),
+            state,
+            auth_state.nonce,
+            code_challenge,
+        );
+        Ok(OidcAuthorizeData { authorize_url, state })
+    }
+
+    async fn authenticate(&self, item: &OidcLoginReq) -> Result<IdTokenClaims> {
+        let config = self.config;
+        // whoever takes the state first wins, a replayed callback finds nothing
+        let value = self.cache.take_string(&format!("{}{}", CACHE_KEY_STATE, item.state)).await?;
+        let Ok(auth_state) = serde_json::from_str::<AuthState>(&value) else {
+            return Error::err("登录已过期,请重新登录")
+        };
+
+        let params = [
+            ("grant_type", "authorization_code"),
+            ("code", item.code.as_str()),
+            ("redirect_uri", config.redirect_uri.as_str()),
+            ("client_id", config.client_id.as_str()),
+            ("client_secret", config.client_secret.as_str()),
+            ("code_verifier", auth_state.code_verifier.as_str()),
+        ];
+        let response = http().post(&self.discovery.token_endpoint).form(&params).send().await.map_err(http_err)?;
+        if !response.status().is_success() {
+            info!("oidc token endpoint: {:?}", response.text().await);
+            return Error::err("单点登录授权码无效")
+        }
+        let token: TokenResponse = response.json().await.map_err(http_err)?;
+
+        let header = decode_header(&token.id_token)?;
+        let decoding_key = self.decoding_key(header.kid.as_deref()).await?;
+        let mut validation = Validation::new(header.alg);
+        validation.set_issuer(&[&self.discovery.issuer]);
+        validation.set_audience(&[&config.client_id]);
+        let claims = decode::<IdTokenClaims>(&token.id_token, &decoding_key, &validation)?.claims;
+        if claims.nonce.as_deref() != Some(auth_state.nonce.as_str()) {
+            return Error::err("单点登录令牌无效")
+        }
+        Ok(claims)
+    }
+
+    /// roles of the group claim from `role_mapping` and the `default_role_ids`
+    fn mapped_roles(&self, claims: &IdTokenClaims) -> Vec<i32> {
+        let config = self.config;
+        user_service::map_group_roles(&claims.groups(&config.group_claim), &config.role_mapping, &config.default_role_ids)
+    }
+}
+
+/// start the authorization code flow with PKCE
+pub async fn authorize() -> Result<OidcAuthorizeData> {
+    provider().await?.authorize().await
+}
+
+/// exchange the code and verify the id token, the state is used once
+pub async fn authenticate(item: &OidcLoginReq) -> Result<IdTokenClaims> {
+    provider().await?.authenticate(item).await
+}
+
+/// the user linked to the subject, linked by mobile or provisioned on first login
+pub async fn find_or_provision(claims: &IdTokenClaims) -> Result<SysUser> {
+    let provider = provider().await?;
+    let config = provider.config;
+    let rb = pool!();
+    let issuer = &provider.discovery.issuer;
+
+    let mut provisioned = false;
+    let link = SysUserOidc::select_by_subject(rb, issuer, &claims.sub).await?;
+    let user = match link {
+        Some(link) => SysUser::select_by_id(rb, link.user_id).await?,
+        None => {
+            let mobile = claims.string(&config.mobile_claim).unwrap_or_default();
+            // only a mobile the provider has verified may take over an existing account
+            let mut user = match config.link_by_mobile && !mobile.is_empty() && claims.is_phone_verified() {
+                true => SysUser::select_by_mobile(rb, &mobile).await?,
+                false => None,
+            };
+            if user.is_none() && config.jit_provision {
+                user = Some(provision(config, claims, &mobile).await?);
+                provisioned = true;
+            }
+            if let Some(user) = &user {
+                let link = SysUserOidc {
+                    id: None,
+                    create_time: Some(DateTime::now()),
+                    user_id: user.id.unwrap_or_default(),
+                    issuer: issuer.clone(),
+                    subject: claims.sub.clone(),
+                };
+                SysUserOidc::insert(rb, &link).await?;
+                info!("oidc link subject {} to user {:?}", claims.sub, user.id);
+            }
+            user
+        }
+    };
+    let Some(user) = user else {
+        return Error::err("单点登录账号未关联用户")
+    };
+
+    // a new user gets the mapped roles once, existing users on every login only with sync_roles
+    if provisioned || config.sync_roles {
+        user_service::sync_user_roles(user.id.unwrap_or_default(), &provider.mapped_roles(claims)).await?;
+    }
+    Ok(user)
+}
+
+async fn provision(config: &OidcConfig, claims: &IdTokenClaims, mobile: &str) -> Result<SysUser> {
+    if mobile.is_empty() {
+        return Error::err("单点登录账号缺少手机号,不能创建用户")
+    }
+    let rb = pool!();
+    if SysUser::select_by_mobile(rb, mobile).await?.is_some() {
+        return Error::err("手机号已被其他用户使用")
+    }
+    let now = Some(DateTime::now());
+    let mut user = SysUser {
+        id: None,
+        create_time: now.clone(),
+        update_time: now.clone(),
+        status_id: 1,
+        sort: 1,
+        mobile: mobile.to_string(),
+        user_name: claims.string(&config.name_claim).unwrap_or_else(|| mobile.to_string()),
+        remark: Some("单点登录创建".to_string()),
+        dept_id: None,
+        // no local password, only the identity provider can log in
+        password: String::new(),
+        password_update_time: now,
+        auth_source: Some(AUTH_SOURCE_OIDC.to_string()),
+        locked_until: None,
+        lock_reason: None,
+        totp_secret: None,
+        totp_recovery: None,
+        last_login_time: None,
+        last_login_ip: None,
+    };
+    let result = SysUser::insert(rb, &user).await?;
+    user.id = result.last_insert_id.as_u64();
+    info!("oidc provision user {:?} {}", user.id, mobile);
+    Ok(user)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use axum::extract::State;
+    use axum::http::StatusCode;
+    use axum::routing::{get, post};
+    use axum::{Form, Json, Router};
+    use base64::engine::general_purpose::STANDARD;
+    use jsonwebtoken::{encode, Algorithm, EncodingKey, Header};
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::utils::get_timestamp;
+
+    const SECRET: &[u8] = b"mock identity provider!!";
+
+    /// the mock identity provider, codes it issued with the challenge and nonce of the authorize request
+    #[derive(Default)]
+    struct Idp {
+        issuer: String,
+        codes: HashMap<String, (String, String)>,
+    }
+
+    type SharedIdp = Arc<Mutex<Idp>>;
+
+    async fn token(State(idp): State<SharedIdp>, Form(form): Form<HashMap<String, String>>) -> std::result::Result<Json<Value>, StatusCode> {
+        let idp = idp.lock().unwrap();
+        let code = form.get("code").ok_or(StatusCode::BAD_REQUEST)?;
+        let (challenge, nonce) = idp.codes.get(code).ok_or(StatusCode::BAD_REQUEST)?;
+        let verifier = form.get("code_verifier").ok_or(StatusCode::BAD_REQUEST)?;
+        if URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes())) != *challenge {
+            return Err(StatusCode::BAD_REQUEST)
+        }
+        let claims = json!({
+            "iss": idp.issuer,
+            "aud": "axum-admin",
+            "sub": "alice",
+            "exp": get_timestamp() + 300,
+            "nonce": nonce,
+            "name": "Alice",
+            "phone_number": "13800000000",
+            "phone_number_verified": true,
+            "groups": ["admins", "unknown"],
+        });
+        let mut header = Header::new(Algorithm::HS256);
+        header.kid = Some("k1".to_string());
+        let id_token = encode(&header, &claims, &EncodingKey::from_secret(SECRET)).unwrap();
+        Ok(Json(json!({ "id_token": id_token, "token_type": "Bearer" })))
+    }
+
+    async fn mock_idp() -> SharedIdp {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let issuer = format!("http://{}", listener.local_addr().unwrap());
+        let discovery = json!({
+            "issuer": issuer,
+            "authorization_endpoint": format!("{}/authorize", issuer),
+            "token_endpoint": format!("{}/token", issuer),
+            "jwks_uri": format!("{}/jwks", issuer),
+        });
+        let jwks = json!({ "keys": [{ "kty": "oct", "kid": "k1", "alg": "HS256", "k": STANDARD.encode(SECRET) }] });
+        let idp = Arc::new(Mutex::new(Idp { issuer, ..Default::default() }));
+        let app = Router::new()
+            .route("/.well-known/openid-configuration", get(move || async move { Json(discovery) }))
+            .route("/jwks", get(move || async move { Json(jwks) }))
+            .route("/token", post(token))
+            .with_state(idp.clone());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        idp
+    }
+
+    async fn provider(idp: &SharedIdp) -> Provider {
+        let config = OidcConfig {
+            issuer: idp.lock().unwrap().issuer.clone(),
+            client_id: "axum-admin".to_string(),
+            client_secret: "secret".to_string(),
+            redirect_uri: "http://localhost:3000/oidc/callback".to_string(),
+            scopes: vec!["openid".to_string(), "phone".to_string()],
+            mobile_claim: "phone_number".to_string(),
+            name_claim: "name".to_string(),
+            group_claim: "groups".to_string(),
+            link_by_mobile: true,
+            jit_provision: true,
+            sync_roles: true,
+            default_role_ids: vec![3],
+            role_mapping: HashMap::from([("admins".to_string(), vec![1, 2])]),
+        };
+        let cache = CacheService::new("mem").unwrap();
+        Provider::discover(Box::leak(Box::new(config)), Box::leak(Box::new(cache))).await.unwrap()
+    }
+
+    /// the user logs in at the identity provider, which redirects back with a code
+    fn login(idp: &SharedIdp, authorize_url: &str) -> String {
+        let query: HashMap<&str, &str> = authorize_url.split_once('?').unwrap().1
+            .split('&')
+            .filter_map(|kv| kv.split_once('='))
+            .collect();
+        assert_eq!(query["code_challenge_method"], "S256");
+        let code = random_string(16);
+        idp.lock().unwrap().codes.insert(code.clone(), (query["code_challenge"].to_string(), query["nonce"].to_string()));
+        code
+    }
+
+    #[tokio::test]
+    async fn test_oidc_login() {
+        let idp = mock_idp().await;
+        let provider = provider(&idp).await;
+
+        let data = provider.authorize().await.unwrap();
+        assert!(data.authorize_url.contains(&format!("state={}", data.state)));
+        let req = OidcLoginReq { code: login(&idp, &data.authorize_url), state: data.state };
+        let claims = provider.authenticate(&req).await.unwrap();
+        assert_eq!(claims.sub, "alice");
+        assert_eq!(claims.string("phone_number").as_deref(), Some("13800000000"));
+        assert!(claims.is_phone_verified());
+        assert_eq!(provider.mapped_roles(&claims), vec![1, 2, 3]);
+
+        // the state is used once
+        assert_eq!(provider.authenticate(&req).await.unwrap_err().msg(), "登录已过期,请重新登录");
+    }
+
+    #[tokio::test]
+    async fn test_oidc_unknown_state() {
+        let idp = mock_idp().await;
+        let provider = provider(&idp).await;
+        let data = provider.authorize().await.unwrap();
+        let req = OidcLoginReq { code: login(&idp, &data.authorize_url), state: random_string(32) };
+        assert_eq!(provider.authenticate(&req).await.unwrap_err().msg(), "登录已过期,请重新登录");
+    }
+
+    #[tokio::test]
+    async fn test_oidc_pkce() {
+        let idp = mock_idp().await;
+        let provider = provider(&idp).await;
+        // the code of one login redeemed with the verifier of another
+        let first = provider.authorize().await.unwrap();
+        let second = provider.authorize().await.unwrap();
+        let req = OidcLoginReq { code: login(&idp, &first.authorize_url), state: second.state };
+        assert_eq!(provider.authenticate(&req).await.unwrap_err().msg(), "单点登录授权码无效");
+    }
+
+    #[tokio::test]
+    async fn test_oidc_nonce() {
+        let idp = mock_idp().await;
+        let provider = provider(&idp).await;
+        let data = provider.authorize().await.unwrap();
+        let code = login(&idp, &data.authorize_url);
+        idp.lock().unwrap().codes.get_mut(&code).unwrap().1 = random_string(32);
+        let req = OidcLoginReq { code, state: data.state };
+        assert_eq!(provider.authenticate(&req).await.unwrap_err().msg(), "单点登录令牌无效");
+    }
+
+    #[test]
+    fn test_phone_verified() {
+        let claims: IdTokenClaims = serde_json::from_value(json!({ "sub": "bob", "phone_number": "13800000000" })).unwrap();
+        assert!(!claims.is_phone_verified());
+        let claims: IdTokenClaims = serde_json::from_value(json!({ "sub": "bob", "phone_number_verified": "true" })).unwrap();
+        assert!(!claims.is_phone_verified());
+    }
+}
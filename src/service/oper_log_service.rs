@@ -0,0 +1,125 @@
+use futures::stream::{self, Stream, StreamExt};
+use rbatis::plugin::page::PageRequest;
+use rbatis::Page;
+use log::info;
+
+use super::CONTEXT;
+use crate::{pool, Result};
+use crate::model::oper_log::SysOperLog;
+use crate::vo::oper_log_vo::*;
+
+pub async fn save(log: &SysOperLog) -> Result<()> {
+    SysOperLog::insert(pool!(), log).await?;
+    Ok(())
+}
+
+// 查询操作日志
+pub async fn oper_log_list(item: OperLogListReq) -> Result<Page<OperLogListData>> {
+    info!("oper_log_list params: {:?}", &item);
+    let filter = &item.filter;
+    let page_req = PageRequest::new(item.page_no, item.page_size);
+    let result = SysOperLog::select_page_by_filter(
+        pool!(),
+        &page_req,
+        filter.user_id,
+        filter.path.as_deref().unwrap_or_default(),
+        filter.code,
+        filter.begin_time.as_deref().unwrap_or_default(),
+        filter.end_time.as_deref().unwrap_or_default(),
+    ).await?;
+
+    Ok(Page::<OperLogListData>::from(result))
+}
+
+/// rows of one export query
+const EXPORT_BATCH: u64 = 500;
+
+/// reads the rows of an export newest first, a batch at a time
+struct ExportCursor {
+    filter: OperLogFilter,
+    before_id: Option<u64>,
+    remaining: u64,
+}
+
+impl ExportCursor {
+    /// the next batch, `None` when the export is done
+    async fn next(&mut self) -> Result<Option<Vec<SysOperLog>>> {
+        if self.remaining == 0 {
+            return Ok(None)
+        }
+        let limit = EXPORT_BATCH.min(self.remaining);
+        let filter = &self.filter;
+        let logs = SysOperLog::select_by_filter(
+            pool!(),
+            filter.user_id,
+            filter.path.as_deref().unwrap_or_default(),
+            filter.code,
+            filter.begin_time.as_deref().unwrap_or_default(),
+            filter.end_time.as_deref().unwrap_or_default(),
+            self.before_id,
+            limit,
+        ).await?;
+        // a short batch is the last one
+        self.remaining = if (logs.len() as u64) < limit { 0 } else { self.remaining - limit };
+        self.before_id = logs.last().and_then(|x| x.id);
+        Ok(Some(logs).filter(|logs| !logs.is_empty()))
+    }
+}
+
+// 导出操作日志为csv, 最多导出`oper_log.export_limit`条, 分批查询边查边写
+pub async fn oper_log_export(filter: OperLogFilter) -> Result<impl Stream<Item = Result<String>>> {
+    info!("oper_log_export params: {:?}", &filter);
+    let mut cursor = ExportCursor {
+        filter,
+        before_id: None,
+        remaining: CONTEXT.config.oper_log.export_limit,
+    };
+    // 第一批在返回前查询, 查询失败时返回错误信息而不是中断的文件
+    let first = cursor.next().await?.unwrap_or_default();
+
+    // BOM让Excel识别utf-8
+    let mut head = String::from("\u{feff}id,时间,用户ID,方法,接口,请求参数,状态,结果码,IP,User-Agent,耗时(ms)\n");
+    head.push_str(&csv_rows(first));
+    let rest = stream::try_unfold(cursor, |mut cursor| async move {
+        Ok(cursor.next().await?.map(|logs| (csv_rows(logs), cursor)))
+    });
+    Ok(stream::once(async { Ok(head) }).chain(rest))
+}
+
+fn csv_rows(logs: Vec<SysOperLog>) -> String {
+    let mut csv = String::new();
+    for log in logs.into_iter().map(OperLogListData::from) {
+        let row = [
+            log.id.to_string(),
+            log.create_time,
+            log.user_id.map(|x| x.to_string()).unwrap_or_default(),
+            log.method,
+            log.path,
+            log.req_body,
+            log.status.to_string(),
+            log.code.map(|x| x.to_string()).unwrap_or_default(),
+            log.ip,
+            log.user_agent,
+            log.latency_ms.to_string(),
+        ];
+        let row: Vec<String> = row.iter().map(|x| csv_field(x)).collect();
+        csv.push_str(&row.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+/// quote the field when it holds a separator, a quote or a line break.
+/// a leading formula character is escaped so spreadsheets don't evaluate it
+fn csv_field(s: &str) -> String {
+    let s = if s.starts_with(['=', '+', '-', '@']) && s.parse::<f64>().is_err() {
+        format!("'{}", s)
+    } else {
+        s.to_string()
+    };
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s
+    }
+}
@@ -0,0 +1,192 @@
+use log::info;
+use rbatis::rbdc::datetime::DateTime;
+
+use super::CONTEXT;
+use crate::error::{Error, Result};
+use crate::error_info;
+use crate::model::password_history::SysPasswordHistory;
+use crate::model::user::SysUser;
+use crate::pool;
+use crate::service::authenticator::local::local_secret;
+use crate::service::{config_service, session_service};
+use crate::utils::notifier::notifier;
+use crate::utils::password::Password;
+use crate::utils::{get_timestamp, random_string};
+
+const CACHE_KEY_RESET: &str = "pwd_reset:";
+const CACHE_KEY_RESET_USER: &str = "pwd_reset_user:";
+const CACHE_KEY_RESET_SEND: &str = "pwd_reset_send:";
+/// seconds between two reset requests for the same mobile
+const RESET_SEND_INTERVAL: u64 = 60;
+
+/// check the plain password against the runtime `password_policy`,
+/// a md5 digest is always refused, it is 32 characters whatever the password was
+pub fn check_policy(password: &str) -> Result<()> {
+    let settings = config_service::settings();
+    let policy = &settings.password_policy;
+    if Password::is_md5(password) {
+        return error_info!("password_need_plain")
+    }
+    if password.chars().count() < policy.min_length {
+        return error_info!("password_too_short", policy.min_length.to_string())
+    }
+    if policy.require_upper && !password.chars().any(|c| c.is_ascii_uppercase()) {
+        return error_info!("password_need_upper")
+    }
+    if policy.require_lower && !password.chars().any(|c| c.is_ascii_lowercase()) {
+        return error_info!("password_need_lower")
+    }
+    if policy.require_digit && !password.chars().any(|c| c.is_ascii_digit()) {
+        return error_info!("password_need_digit")
+    }
+    if policy.require_special && password.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return error_info!("password_need_special")
+    }
+    Ok(())
+}
+
+/// the hash stored for a plain password, of the same digest the login page sends
+pub fn hash(password: &str) -> String {
+    Password::hash(local_secret(password))
+}
+
+/// the password must not be the current one or one of the last `history` passwords of the user
+pub async fn check_history(user: &SysUser, password: &str) -> Result<()> {
+    let history = config_service::settings().password_policy.history;
+    if history == 0 {
+        return Ok(())
+    }
+    let recent = SysPasswordHistory::select_recent(pool!(), user.id.unwrap_or_default(), history).await?;
+    let secret = local_secret(password);
+    if Password::verify(&secret, &user.password) || recent.iter().any(|h| Password::verify(&secret, &h.password)) {
+        return error_info!("password_reused", history.to_string())
+    }
+    Ok(())
+}
+
+/// store the new hash of the user and record it in the history
+pub async fn save_password(user_id: u64, hash: &str) -> Result<()> {
+    let rb = pool!();
+    SysUser::change_password(rb, user_id, hash, DateTime::now()).await?;
+    add_history(user_id, hash).await
+}
+
+/// record the hash, only the last `history` are kept
+pub async fn add_history(user_id: u64, hash: &str) -> Result<()> {
+    let history = config_service::settings().password_policy.history;
+    if history == 0 {
+        return Ok(())
+    }
+    let rb = pool!();
+    let item = SysPasswordHistory {
+        id: None,
+        create_time: Some(DateTime::now()),
+        user_id,
+        password: hash.to_string(),
+    };
+    SysPasswordHistory::insert(rb, &item).await?;
+
+    let recent = SysPasswordHistory::select_recent(rb, user_id, history).await?;
+    if let Some(oldest) = recent.last().and_then(|h| h.id).filter(|_| recent.len() as u64 == history) {
+        SysPasswordHistory::delete_before(rb, user_id, oldest).await?;
+    }
+    Ok(())
+}
+
+/// the password must be changed before login: it is the default one set when the user
+/// was created (no update time), or it is older than `max_age_days`
+pub fn is_expired(user: &SysUser) -> bool {
+    if user.password_update_time.is_none() {
+        return true
+    }
+    let max_age_days = config_service::settings().password_policy.max_age_days;
+    if max_age_days == 0 {
+        return false
+    }
+    let updated = user.password_update_time.as_ref().map_or(0, |t| t.unix_timestamp());
+    updated + ((max_age_days * 86400) as i64) < get_timestamp() as i64
+}
+
+/// issue a single-use reset token and deliver it through the notifier.
+/// unknown mobiles get the same answer, nothing is sent
+pub async fn forgot_password(mobile: &str) -> Result<()> {
+    let send_key = format!("{}{}", CACHE_KEY_RESET_SEND, mobile);
+    if CONTEXT.cache_service.incr(&send_key, RESET_SEND_INTERVAL).await? > 1 {
+        let wait_sec = CONTEXT.cache_service.ttl(&send_key).await?.max(1);
+        return error_info!("req_frequently", wait_sec.to_string())
+    }
+
+    let Some(user) = SysUser::select_by_mobile(pool!(), mobile).await? else {
+        info!("forgot_password unknown mobile: {}", mobile);
+        return Ok(())
+    };
+    let user_id = user.id.unwrap_or_default();
+    let exp = CONTEXT.config.password_reset_exp;
+
+    // only the newest token of the user is valid
+    let user_key = format!("{}{}", CACHE_KEY_RESET_USER, user_id);
+    let old_token = CONTEXT.cache_service.get_string(&user_key).await?;
+    if !old_token.is_empty() {
+        CONTEXT.cache_service.remove(&format!("{}{}", CACHE_KEY_RESET, old_token)).await?;
+    }
+    let token = random_string(48);
+    CONTEXT
+        .cache_service
+        .set_string(&format!("{}{}", CACHE_KEY_RESET, token), &user_id.to_string(), exp)
+        .await?;
+    CONTEXT.cache_service.set_string(&user_key, &token, exp).await?;
+
+    let content = format!("密码重置令牌: {}, {}分钟内有效", token, exp / 60);
+    notifier().notify(mobile, "重置密码", &content).await
+}
+
+/// set the new password with a reset token, all sessions of the user are kicked
+pub async fn reset_password(token: &str, new_password: &str) -> Result<()> {
+    let key = format!("{}{}", CACHE_KEY_RESET, token);
+    let user_id: u64 = CONTEXT.cache_service.get_string(&key).await?.parse().unwrap_or_default();
+    if user_id == 0 {
+        return error_info!("reset_token_invalid")
+    }
+    let user = match SysUser::select_by_id(pool!(), user_id).await? {
+        Some(user) if user.status_id == 1 => user,
+        Some(_) => return error_info!("account_disabled"),
+        None => return error_info!("account_not_exists"),
+    };
+    if !user.is_local() {
+        return Error::err("外部认证的账号不能修改密码")
+    }
+    check_policy(new_password)?;
+    check_history(&user, new_password).await?;
+
+    // the token is used once, whoever removes it first wins
+    if !CONTEXT.cache_service.remove(&key).await? {
+        return error_info!("reset_token_invalid")
+    }
+    CONTEXT.cache_service.remove(&format!("{}{}", CACHE_KEY_RESET_USER, user_id)).await?;
+
+    save_password(user_id, &hash(new_password)).await?;
+    let count = session_service::kick_user(user_id).await?;
+    info!("reset_password user: {}, kicked sessions: {}", user_id, count);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn code_of(result: Result<()>) -> String {
+        match result {
+            Err(Error::Code(code, _)) => code,
+            r => format!("{:?}", r),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_check_policy_default() {
+        // the default policy only has a minimum length, which any digest passes
+        assert_eq!(code_of(check_policy(&Password::md5("1"))), "password_need_plain");
+        assert_eq!(code_of(check_policy(&Password::md5("1").to_uppercase())), "password_need_plain");
+        assert_eq!(code_of(check_policy("1")), "password_too_short");
+        assert!(check_policy("123456").is_ok());
+    }
+}
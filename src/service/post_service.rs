@@ -0,0 +1,78 @@
+use std::collections::HashMap;
+
+use rbatis::plugin::page::PageRequest;
+use rbatis::rbdc::datetime::DateTime;
+use rbatis::Page;
+
+use crate::{pool, Error, Result};
+use crate::model::post::SysPost;
+use crate::model::user_post::SysUserPost;
+use crate::vo::post_vo::*;
+
+// 查询岗位列表
+pub async fn post_list(item: PostListReq) -> Result<Page<PostListData>> {
+    let rb = pool!();
+
+    let post_name = item.post_name.as_deref().unwrap_or_default();
+    let status_id = item.status_id.as_deref().unwrap_or_default();
+
+    let page_req = PageRequest::new(item.page_no, item.page_size);
+    let result = SysPost::select_page_by_name(rb, &page_req, post_name, status_id).await?;
+
+    Ok(Page::<PostListData>::from(result))
+}
+
+// 添加岗位信息
+pub async fn post_save(item: PostSaveReq) -> Result<u64> {
+    let rb = pool!();
+
+    let sys_post = SysPost::from(item);
+    let result = SysPost::insert(rb, &sys_post).await?;
+    Ok(result.rows_affected)
+}
+
+// 更新岗位信息
+pub async fn post_update(mut item: PostUpdateReq) -> Result<u64> {
+    let rb = pool!();
+    item.update_time = Some(DateTime::now());
+
+    let result = PostUpdateReq::update_by_column(rb, &item, "id").await?;
+    Ok(result.rows_affected)
+}
+
+// 删除岗位信息
+pub async fn post_delete(item: PostDeleteReq) -> Result<u64> {
+    let rb = pool!();
+
+    let user_post_list = SysUserPost::select_in_column(rb, "post_id", &item.ids).await?;
+    if !user_post_list.is_empty() {
+        return Error::err("岗位已分配给用户,不能直接删除")
+    }
+    let result = SysPost::delete_in_column(rb, "id", &item.ids).await?;
+    Ok(result.rows_affected)
+}
+
+// 查询用户的岗位名称, 用于用户列表展示
+pub async fn post_names_of(user_ids: &[u64]) -> Result<HashMap<u64, Vec<String>>> {
+    let mut result: HashMap<u64, Vec<String>> = HashMap::new();
+    if user_ids.is_empty() {
+        return Ok(result)
+    }
+    let rb = pool!();
+    let user_posts = SysUserPost::select_in_column(rb, "user_id", user_ids).await?;
+    if user_posts.is_empty() {
+        return Ok(result)
+    }
+    let post_ids: Vec<i32> = user_posts.iter().map(|x| x.post_id).collect();
+    let posts: HashMap<i32, String> = SysPost::select_in_column(rb, "id", &post_ids).await?
+        .into_iter()
+        .filter_map(|x| Some((x.id?, x.post_name)))
+        .collect();
+
+    for user_post in user_posts {
+        if let Some(name) = posts.get(&user_post.post_id) {
+            result.entry(user_post.user_id).or_default().push(name.clone());
+        }
+    }
+    Ok(result)
+}
@@ -1,9 +1,11 @@
-use crate::{pool, Result};
+use crate::{pool, Error, Result};
+use crate::service::token_service;
 use rbatis::plugin::page::PageRequest;
 use rbatis::Page;
 use log::info;
 use crate::model::menu::SysMenu;
-use crate::model::role::SysRole;
+use crate::model::role::{SysRole, DATA_SCOPE_CUSTOM};
+use crate::model::role_dept::SysRoleDept;
 use crate::model::role_menu::{query_menu_by_role, SysRoleMenu};
 use crate::model::user_role::SysUserRole;
 use crate::vo::role_vo::*;
@@ -41,6 +43,7 @@ pub async fn role_update(item: RoleUpdateReq) -> Result<u64> {
 
     // let sys_role = SysRole::from(item);
     let result = RoleUpdateReq::update_by_column(rb, &item, "id").await?;
+    bump_role_perm_version(&[item.id]).await?;
     Ok(result.rows_affected)
 }
 
@@ -55,6 +58,7 @@ pub async fn role_delete(item: RoleDeleteReq) -> Result<u64>  {
         return Err("角色已被使用,不能直接删除".into());
     }
     let result = SysRole::delete_in_column(rb, "id", &item.ids).await?;
+    bump_role_perm_version(&item.ids).await?;
     Ok(result.rows_affected)
 }
 
@@ -109,5 +113,46 @@ pub async fn update_role_menu(item: UpdateRoleMenuReq) -> Result<u64> {
     }
 
     let result = SysRoleMenu::insert_batch(rb, &menu_role, item.menu_ids.len() as u64).await?;
+    bump_role_perm_version(&[role_id]).await?;
     Ok(result.rows_affected)
 }
+
+// 查询角色自定义数据范围的部门
+pub async fn query_role_dept(item: QueryRoleDeptReq) -> Result<QueryRoleDeptData> {
+    let rb = pool!();
+    let dept_ids = SysRoleDept::select_by_column(rb, "role_id", item.role_id).await?
+        .into_iter().map(|x| x.dept_id).collect();
+    Ok(QueryRoleDeptData { dept_ids })
+}
+
+// 更新角色自定义数据范围的部门
+pub async fn update_role_dept(item: UpdateRoleDeptReq) -> Result<u64> {
+    let rb = pool!();
+    let role_id = item.role_id;
+    match SysRole::select_by_column(rb, "id", role_id).await?.into_iter().next() {
+        Some(role) if role.data_scope == DATA_SCOPE_CUSTOM => {}
+        Some(_) => return Error::err("只有自定义数据范围的角色可以设置部门"),
+        None => return Error::err("角色不存在"),
+    }
+
+    let _ = SysRoleDept::delete_by_column(rb, "role_id", &role_id).await?;
+    if item.dept_ids.is_empty() {
+        return Ok(0)
+    }
+    let role_dept: Vec<SysRoleDept> = item.dept_ids.iter()
+        .map(|dept_id| SysRoleDept::new(role_id, *dept_id))
+        .collect();
+
+    let result = SysRoleDept::insert_batch(rb, &role_dept, item.dept_ids.len() as u64).await?;
+    Ok(result.rows_affected)
+}
+
+// 角色权限变更后, 这些角色下用户已签发的令牌需要重新获取权限
+async fn bump_role_perm_version(role_ids: &[i32]) -> Result<()> {
+    let rb = pool!();
+    let mut user_ids: Vec<u64> = SysUserRole::select_in_column(rb, "role_id", role_ids).await?
+        .into_iter().map(|x| x.user_id).collect();
+    user_ids.sort_unstable();
+    user_ids.dedup();
+    token_service::bump_perm_version(&user_ids).await
+}
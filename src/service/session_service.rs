@@ -0,0 +1,176 @@
+use log::info;
+
+use super::{config_service, CONTEXT};
+use crate::error::Result;
+use crate::middleware::context::ClientInfo;
+use crate::service::token_service;
+use crate::utils::get_timestamp;
+use crate::utils::jwt_util::JWTToken;
+use crate::vo::session_vo::*;
+
+// 每个会话一个key, 用户的会话和在线用户是集合索引, 过期的成员在查询时清理
+const CACHE_KEY_SESSION: &str = "session:";
+const CACHE_KEY_USER_SESSIONS: &str = "session:user:";
+const CACHE_KEY_ONLINE_USERS: &str = "session:online_users";
+
+/// a session lives as long as its refresh token
+fn session_ttl() -> u64 {
+    config_service::settings().jwt_refresh_exp
+}
+
+/// register a new session at login
+pub async fn create(token: &JWTToken, client: &ClientInfo) -> Result<()> {
+    let now = get_timestamp();
+    let session = SessionInfo {
+        session_id: token.jti.clone(),
+        user_id: token.id,
+        user_name: token.username.clone(),
+        ip: client.ip.clone(),
+        user_agent: client.user_agent.clone(),
+        device: client.device().to_string(),
+        login_time: now,
+        active_time: now,
+        token_exp: token.exp(),
+    };
+    // 先写会话再写索引, 索引中的会话不存在时就是已过期
+    CONTEXT
+        .cache_service
+        .set_json(&format!("{}{}", CACHE_KEY_SESSION, token.jti), &session, session_ttl())
+        .await?;
+    add_index(token.id, &token.jti).await
+}
+
+// the indexes live as long as their newest session
+async fn add_index(user_id: u64, jti: &str) -> Result<()> {
+    CONTEXT
+        .cache_service
+        .set_add(&format!("{}{}", CACHE_KEY_USER_SESSIONS, user_id), jti, session_ttl())
+        .await?;
+    CONTEXT
+        .cache_service
+        .set_add(CACHE_KEY_ONLINE_USERS, &user_id.to_string(), session_ttl())
+        .await
+}
+
+/// the session refreshed its token, extend it
+pub async fn touch(token: &JWTToken, client: &ClientInfo) -> Result<()> {
+    let key = format!("{}{}", CACHE_KEY_SESSION, token.jti);
+    let session: Option<SessionInfo> = CONTEXT.cache_service.get_json(&key).await?;
+    if let Some(mut session) = session {
+        session.ip = client.ip.clone();
+        session.user_agent = client.user_agent.clone();
+        session.active_time = get_timestamp();
+        session.token_exp = token.exp();
+        CONTEXT.cache_service.set_json(&key, &session, session_ttl()).await?;
+        add_index(session.user_id, &session.session_id).await?;
+    }
+    Ok(())
+}
+
+/// the token was re-signed with a later expiry
+pub async fn extend(token: &JWTToken) -> Result<()> {
+    let key = format!("{}{}", CACHE_KEY_SESSION, token.jti);
+    let session: Option<SessionInfo> = CONTEXT.cache_service.get_json(&key).await?;
+    if let Some(mut session) = session {
+        session.token_exp = token.exp();
+        CONTEXT.cache_service.set_json(&key, &session, session_ttl()).await?;
+    }
+    Ok(())
+}
+
+/// expiry of the newest token of the session,
+/// the longest possible lifetime when the session is unknown
+pub async fn token_exp(jti: &str) -> Result<u64> {
+    let session: Option<SessionInfo> = CONTEXT.cache_service.get_json(&format!("{}{}", CACHE_KEY_SESSION, jti)).await?;
+    match session {
+        Some(session) if session.token_exp > 0 => Ok(session.token_exp),
+        _ => Ok(get_timestamp() + config_service::settings().jwt_exp),
+    }
+}
+
+/// remove the session at logout
+pub async fn remove(jti: &str) -> Result<bool> {
+    let key = format!("{}{}", CACHE_KEY_SESSION, jti);
+    let session: Option<SessionInfo> = CONTEXT.cache_service.get_json(&key).await?;
+    let removed = CONTEXT.cache_service.remove(&key).await?;
+    if let Some(session) = session {
+        CONTEXT
+            .cache_service
+            .set_remove(&format!("{}{}", CACHE_KEY_USER_SESSIONS, session.user_id), jti)
+            .await?;
+    }
+    Ok(removed)
+}
+
+/// active sessions of the user, expired sessions are dropped from the index
+pub async fn list(user_id: u64) -> Result<Vec<SessionInfo>> {
+    let user_key = format!("{}{}", CACHE_KEY_USER_SESSIONS, user_id);
+    let session_ids = CONTEXT.cache_service.set_members(&user_key).await?;
+
+    let mut sessions = Vec::with_capacity(session_ids.len());
+    for id in &session_ids {
+        let session: Option<SessionInfo> = CONTEXT
+            .cache_service
+            .get_json(&format!("{}{}", CACHE_KEY_SESSION, id))
+            .await?;
+        match session {
+            Some(session) => sessions.push(session),
+            None => CONTEXT.cache_service.set_remove(&user_key, id).await?,
+        }
+    }
+    Ok(sessions)
+}
+
+/// users with at least one active session
+pub async fn online_users() -> Result<Vec<OnlineUserData>> {
+    let user_ids = CONTEXT.cache_service.set_members(CACHE_KEY_ONLINE_USERS).await?;
+
+    let mut result = Vec::with_capacity(user_ids.len());
+    for id in &user_ids {
+        let Ok(user_id) = id.parse::<u64>() else {
+            CONTEXT.cache_service.set_remove(CACHE_KEY_ONLINE_USERS, id).await?;
+            continue
+        };
+        let sessions = list(user_id).await?;
+        match sessions.iter().max_by_key(|s| s.active_time) {
+            Some(last) => result.push(OnlineUserData {
+                user_id,
+                user_name: last.user_name.clone(),
+                session_count: sessions.len(),
+                ip: last.ip.clone(),
+                active_time: last.active_time,
+            }),
+            None => prune_online_user(user_id, id).await?,
+        }
+    }
+    Ok(result)
+}
+
+// 移除没有会话的用户, 期间用户又登录时加回去
+async fn prune_online_user(user_id: u64, id: &str) -> Result<()> {
+    CONTEXT.cache_service.set_remove(CACHE_KEY_ONLINE_USERS, id).await?;
+    let user_key = format!("{}{}", CACHE_KEY_USER_SESSIONS, user_id);
+    if !CONTEXT.cache_service.set_members(&user_key).await?.is_empty() {
+        CONTEXT.cache_service.set_add(CACHE_KEY_ONLINE_USERS, id, session_ttl()).await?;
+    }
+    Ok(())
+}
+
+/// terminate sessions, their tokens are revoked and refresh tokens removed
+pub async fn kick(session_ids: &[String]) -> Result<u64> {
+    let mut count = 0;
+    for id in session_ids {
+        info!("kick session: {}", id);
+        token_service::revoke(id, token_exp(id).await?).await?;
+        if remove(id).await? {
+            count += 1;
+        }
+    }
+    Ok(count)
+}
+
+/// terminate all sessions of the user
+pub async fn kick_user(user_id: u64) -> Result<u64> {
+    let session_ids: Vec<String> = list(user_id).await?.into_iter().map(|s| s.session_id).collect();
+    kick(&session_ids).await
+}
@@ -0,0 +1,130 @@
+use log::info;
+use rand::Rng;
+
+use super::CONTEXT;
+use crate::error::Result;
+use crate::error_info;
+use crate::model::user::SysUser;
+use crate::pool;
+use crate::utils::cache::cache_service::CacheService;
+use crate::utils::sms::sms_sender;
+
+const CACHE_KEY_CODE: &str = "sms:code:";
+const CACHE_KEY_SEND: &str = "sms:send:";
+const CACHE_KEY_ATTEMPT: &str = "sms:attempt:";
+/// wrong codes allowed before the code is discarded
+const MAX_ATTEMPTS: i64 = 5;
+
+/// send a login code, at most once per `sms_send_interval` for a mobile.
+/// unknown mobiles get the same answer, nothing is sent
+pub async fn send_code(mobile: &str) -> Result<()> {
+    throttle(&CONTEXT.cache_service, mobile, CONTEXT.config.sms_send_interval).await?;
+
+    if SysUser::select_by_mobile(pool!(), mobile).await?.is_none() {
+        info!("send_code to unknown mobile: {}", mobile);
+        return Ok(())
+    }
+
+    let code = format!("{:06}", rand::thread_rng().gen_range(0..1_000_000));
+    CONTEXT
+        .cache_service
+        .set_string(&format!("{}{}", CACHE_KEY_CODE, mobile), &code, CONTEXT.config.sms_code_exp)
+        .await?;
+    CONTEXT.cache_service.remove(&format!("{}{}", CACHE_KEY_ATTEMPT, mobile)).await?;
+    sms_sender().send_code(mobile, &code).await
+}
+
+async fn throttle(cache: &CacheService, mobile: &str, interval: u64) -> Result<()> {
+    let send_key = format!("{}{}", CACHE_KEY_SEND, mobile);
+    if cache.incr(&send_key, interval).await? > 1 {
+        let wait_sec = cache.ttl(&send_key).await?.max(1);
+        return error_info!("req_frequently", wait_sec.to_string())
+    }
+    Ok(())
+}
+
+/// check the code, it can be used once
+pub async fn verify_code(mobile: &str, code: &str) -> Result<()> {
+    check(&CONTEXT.cache_service, mobile, code).await
+}
+
+async fn check(cache: &CacheService, mobile: &str, code: &str) -> Result<()> {
+    let code_key = format!("{}{}", CACHE_KEY_CODE, mobile);
+    let attempt_key = format!("{}{}", CACHE_KEY_ATTEMPT, mobile);
+    let ttl = cache.ttl(&code_key).await?;
+    // of parallel requests only one gets the code, a wrong one puts it back
+    let stored = cache.take_string(&code_key).await?;
+    if stored.is_empty() {
+        return error_info!("please_send_code")
+    }
+    if stored != code.trim() {
+        let attempts = cache.incr(&attempt_key, ttl.max(1) as u64).await?;
+        if attempts < MAX_ATTEMPTS && ttl > 0 {
+            cache.set_string(&code_key, &stored, ttl as u64).await?;
+        } else {
+            cache.remove(&attempt_key).await?;
+        }
+        return error_info!("verify_code_error")
+    }
+
+    cache.remove(&attempt_key).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Error;
+
+    fn code_of(result: Result<()>) -> String {
+        match result {
+            Err(Error::Code(code, _)) => code,
+            r => format!("{:?}", r),
+        }
+    }
+
+    async fn cache_with_code(mobile: &str, code: &str) -> CacheService {
+        let cache = CacheService::new("mem").unwrap();
+        cache.set_string(&format!("{}{}", CACHE_KEY_CODE, mobile), code, 300).await.unwrap();
+        cache
+    }
+
+    #[tokio::test]
+    async fn test_throttle() {
+        let cache = CacheService::new("mem").unwrap();
+        assert!(throttle(&cache, "13800000000", 60).await.is_ok());
+        assert_eq!(code_of(throttle(&cache, "13800000000", 60).await), "req_frequently");
+        assert!(throttle(&cache, "13800000001", 60).await.is_ok());
+        assert!(cache.ttl(&format!("{}{}", CACHE_KEY_SEND, "13800000000")).await.unwrap() > 0);
+    }
+
+    #[tokio::test]
+    async fn test_check_once() {
+        let cache = cache_with_code("13800000000", "123456").await;
+        assert!(check(&cache, "13800000000", "123456").await.is_ok());
+        assert_eq!(code_of(check(&cache, "13800000000", "123456").await), "please_send_code");
+    }
+
+    #[tokio::test]
+    async fn test_check_parallel() {
+        let cache = cache_with_code("13800000000", "123456").await;
+        let (a, b) = tokio::join!(check(&cache, "13800000000", "123456"), check(&cache, "13800000000", "123456"));
+        assert!(a.is_ok() != b.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_check_attempts() {
+        let cache = cache_with_code("13800000000", "123456").await;
+        for _ in 1..MAX_ATTEMPTS {
+            assert_eq!(code_of(check(&cache, "13800000000", "000000").await), "verify_code_error");
+        }
+        // a wrong code keeps the right one usable until the last attempt
+        assert!(check(&cache, "13800000000", "123456").await.is_ok());
+
+        let cache = cache_with_code("13800000000", "123456").await;
+        for _ in 0..MAX_ATTEMPTS {
+            assert_eq!(code_of(check(&cache, "13800000000", "000000").await), "verify_code_error");
+        }
+        assert_eq!(code_of(check(&cache, "13800000000", "123456").await), "please_send_code");
+    }
+}
@@ -1,5 +1,5 @@
 use crate::pool;
-use crate::service::CONTEXT;
+use crate::service::config_service;
 use parking_lot::Mutex;
 use rbatis::executor::Executor;
 use rbatis::intercept::{Intercept, ResultType};
@@ -65,10 +65,15 @@ impl SysTrashService {
 
     //recycle trash older than `trash_recycle_days`
     pub async fn recycle(&self) -> Result<u64, Error> {
-        let before = DateTime::now().0.sub(Duration::from_secs(
-            CONTEXT.config.trash_recycle_days * 24 * 3600,
-        ));
-        let r = SysTrash::delete_by_day_before(pool!(), DateTime(before)).await?;
+        let days = config_service::settings().trash_recycle_days;
+        let before = days
+            .checked_mul(24 * 3600)
+            .and_then(|sec| i64::try_from(sec).ok())
+            .and_then(|sec| DateTime::now().unix_timestamp().checked_sub(sec));
+        let Some(before) = before else {
+            return Err(Error::from(format!("trash_recycle_days out of range: {}", days)));
+        };
+        let r = SysTrash::delete_by_day_before(pool!(), DateTime::from_timestamp(before)).await?;
         Ok(r.rows_affected)
     }
 }
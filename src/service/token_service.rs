@@ -0,0 +1,146 @@
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+use super::{config_service, session_service, CONTEXT};
+use crate::error::Result;
+use crate::Error;
+use crate::utils::jwt_util::JWTToken;
+use crate::utils::{get_timestamp, random_string};
+
+const CACHE_KEY_REVOKED: &str = "jwt:revoked:";
+const CACHE_KEY_REFRESH: &str = "jwt:refresh:";
+const CACHE_KEY_REFRESH_JTI: &str = "jwt:refresh_jti:";
+const CACHE_KEY_REFRESH_USED: &str = "jwt:refresh_used:";
+const CACHE_KEY_PERM_VER: &str = "jwt:perm_ver:";
+const CACHE_KEY_PERM_VER_ALL: &str = "jwt:perm_ver:all";
+
+/// the session a refresh token belongs to
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RefreshTokenInfo {
+    pub user_id: u64,
+    pub jti: String,
+}
+
+/// put the session's jti on the revocation list until `exp`, the expiry of
+/// the newest token of the session. the jti is kept when the token is refreshed
+pub async fn revoke(jti: &str, exp: u64) -> Result<()> {
+    let ttl = exp.saturating_sub(get_timestamp()).max(1);
+    CONTEXT
+        .cache_service
+        .set_string(&format!("{}{}", CACHE_KEY_REVOKED, jti), "1", ttl)
+        .await?;
+    remove_refresh_token(jti).await
+}
+
+/// is the jti on the revocation list
+pub async fn is_revoked(jti: &str) -> Result<bool> {
+    let v = CONTEXT
+        .cache_service
+        .get_string(&format!("{}{}", CACHE_KEY_REVOKED, jti))
+        .await?;
+    Ok(!v.is_empty())
+}
+
+/// reject revoked tokens and tokens issued before the user's permissions changed
+pub async fn check_token(token: &JWTToken) -> Result<()> {
+    if is_revoked(&token.jti).await? {
+        return Error::err(format!("token {} revoked", token.jti));
+    }
+    if perm_version(token.id).await? != token.ver {
+        return Error::err(format!("permissions of user {} changed", token.id));
+    }
+    Ok(())
+}
+
+/// current permission version of the user, the user's own version plus the
+/// version shared by all users. both only increase, so does the sum
+pub async fn perm_version(user_id: u64) -> Result<i64> {
+    let own = CONTEXT
+        .cache_service
+        .get_string(&format!("{}{}", CACHE_KEY_PERM_VER, user_id))
+        .await?;
+    let all = CONTEXT.cache_service.get_string(CACHE_KEY_PERM_VER_ALL).await?;
+    Ok(own.parse::<i64>().unwrap_or_default() + all.parse::<i64>().unwrap_or_default())
+}
+
+/// bump when roles, role menus or status of the users change,
+/// tokens issued before are rejected and have to be refreshed
+pub async fn bump_perm_version(user_ids: &[u64]) -> Result<()> {
+    for user_id in user_ids {
+        CONTEXT
+            .cache_service
+            .incr(&format!("{}{}", CACHE_KEY_PERM_VER, user_id), 0)
+            .await?;
+    }
+    Ok(())
+}
+
+/// bump when menus change, tokens of all users are rejected and have to be refreshed
+pub async fn bump_all_perm_version() -> Result<()> {
+    CONTEXT.cache_service.incr(CACHE_KEY_PERM_VER_ALL, 0).await?;
+    Ok(())
+}
+
+/// create a long-lived refresh token bound to the token's session (jti)
+pub async fn create_refresh_token(token: &JWTToken) -> Result<String> {
+    let refresh_token = random_string(48);
+    let ex = config_service::settings().jwt_refresh_exp;
+    let info = RefreshTokenInfo {
+        user_id: token.id,
+        jti: token.jti.clone(),
+    };
+    CONTEXT
+        .cache_service
+        .set_json(&format!("{}{}", CACHE_KEY_REFRESH, refresh_token), &info, ex)
+        .await?;
+    CONTEXT
+        .cache_service
+        .set_string(&format!("{}{}", CACHE_KEY_REFRESH_JTI, token.jti), &refresh_token, ex)
+        .await?;
+    Ok(refresh_token)
+}
+
+/// take the refresh token out of the cache, every refresh token can only be used once.
+/// a rotated token used again was stolen, or the thief used it first,
+/// so the whole session is terminated
+pub async fn take_refresh_token(refresh_token: &str) -> Result<Option<RefreshTokenInfo>> {
+    let used_key = format!("{}{}", CACHE_KEY_REFRESH_USED, refresh_token);
+    let v = CONTEXT
+        .cache_service
+        .take_string(&format!("{}{}", CACHE_KEY_REFRESH, refresh_token))
+        .await?;
+    if v.is_empty() {
+        let jti = CONTEXT.cache_service.get_string(&used_key).await?;
+        if !jti.is_empty() {
+            warn!("refresh token of session {} reused, terminate the session", jti);
+            session_service::kick(&[jti]).await?;
+        }
+        return Ok(None)
+    }
+
+    let info: RefreshTokenInfo = serde_json::from_str(&v)
+        .map_err(|e| Error::Internal(format!("refresh token info {}: {}", v, e)))?;
+    CONTEXT
+        .cache_service
+        .set_string(&used_key, &info.jti, config_service::settings().jwt_refresh_exp)
+        .await?;
+    CONTEXT
+        .cache_service
+        .remove(&format!("{}{}", CACHE_KEY_REFRESH_JTI, info.jti))
+        .await?;
+    Ok(Some(info))
+}
+
+/// remove the refresh token of the session
+pub async fn remove_refresh_token(jti: &str) -> Result<()> {
+    let jti_key = format!("{}{}", CACHE_KEY_REFRESH_JTI, jti);
+    let refresh_token = CONTEXT.cache_service.get_string(&jti_key).await?;
+    if !refresh_token.is_empty() {
+        CONTEXT
+            .cache_service
+            .remove(&format!("{}{}", CACHE_KEY_REFRESH, refresh_token))
+            .await?;
+        CONTEXT.cache_service.remove(&jti_key).await?;
+    }
+    Ok(())
+}
@@ -0,0 +1,255 @@
+use log::info;
+use serde::{Deserialize, Serialize};
+
+use super::CONTEXT;
+use crate::error::Result;
+use crate::model::user::SysUser;
+use crate::model::user_role::count_require_2fa_role;
+use crate::utils::password::Password;
+use crate::utils::{get_timestamp, random_string, totp};
+use crate::vo::user_vo::*;
+use crate::{error_info, pool, Error};
+
+const CACHE_KEY_PENDING: &str = "totp:pending:";
+const CACHE_KEY_CHALLENGE: &str = "totp:challenge:";
+const CACHE_KEY_CHALLENGE_FAIL: &str = "totp:challenge_fail:";
+const CACHE_KEY_LAST_STEP: &str = "totp:last_step:";
+const CACHE_KEY_RECOVERY_TRY: &str = "totp:recovery_try:";
+/// seconds to confirm the enrolment
+const PENDING_TTL: u64 = 600;
+/// seconds to finish the second login step
+const CHALLENGE_TTL: u64 = 300;
+const CHALLENGE_MAX_FAIL: i64 = 5;
+const RECOVERY_CODE_NUM: usize = 10;
+/// recovery code tries of a user in `RECOVERY_TRY_TTL` seconds, every try hashes against all the codes
+const RECOVERY_MAX_TRY: i64 = 5;
+const RECOVERY_TRY_TTL: u64 = 900;
+
+/// the password is right, waiting for the second factor
+#[derive(Debug, Serialize, Deserialize)]
+struct TotpChallenge {
+    user_id: u64,
+    /// unix seconds, a challenge put back after a failed code keeps its expiry
+    expire_at: u64,
+    /// the role requires 2fa but the user has not enrolled, enroll this secret with the first code
+    enroll_secret: Option<String>,
+}
+
+pub fn has_totp(user: &SysUser) -> bool {
+    user.totp_secret.as_deref().is_some_and(|s| !s.is_empty())
+}
+
+/// enabled by the user or required by one of the user's roles
+pub async fn is_required(user: &SysUser) -> Result<bool> {
+    if has_totp(user) {
+        return Ok(true);
+    }
+    let count = count_require_2fa_role(pool!(), user.id.unwrap_or_default()).await?;
+    Ok(count > 0)
+}
+
+/// start the second login step, return the challenge token and the secret to enroll if the user has none
+pub async fn create_challenge(user: &SysUser) -> Result<(String, Option<TotpEnrollData>)> {
+    let enroll = if has_totp(user) { None } else { Some(new_enroll(&user.mobile)) };
+    let challenge = TotpChallenge {
+        user_id: user.id.unwrap_or_default(),
+        expire_at: get_timestamp() + CHALLENGE_TTL,
+        enroll_secret: enroll.as_ref().map(|e| e.secret.clone()),
+    };
+    let challenge_token = random_string(48);
+    CONTEXT
+        .cache_service
+        .set_json(&format!("{}{}", CACHE_KEY_CHALLENGE, challenge_token), &challenge, CHALLENGE_TTL)
+        .await?;
+    Ok((challenge_token, enroll))
+}
+
+/// check the code of the second login step, return the user and the recovery codes when enrolled at login
+pub async fn verify_challenge(item: &LoginTotpReq) -> Result<(SysUser, Option<Vec<String>>)> {
+    let key = format!("{}{}", CACHE_KEY_CHALLENGE, item.challenge_token);
+    // taken so parallel requests can't try the same challenge, it is put back after a failed code
+    let value = CONTEXT.cache_service.take_string(&key).await?;
+    let Ok(challenge) = serde_json::from_str::<TotpChallenge>(&value) else {
+        return error_info!("totp_challenge_expired");
+    };
+
+    match check_challenge(&challenge, &item.code).await {
+        Ok(Some(verified)) => Ok(verified),
+        Ok(None) => {
+            let fail_key = format!("{}{}", CACHE_KEY_CHALLENGE_FAIL, item.challenge_token);
+            let fail = CONTEXT.cache_service.incr(&fail_key, CHALLENGE_TTL).await?;
+            if fail < CHALLENGE_MAX_FAIL {
+                put_back(&key, &challenge).await?;
+            }
+            error_info!("verify_code_error")
+        }
+        Err(e) => {
+            put_back(&key, &challenge).await?;
+            Err(e)
+        }
+    }
+}
+
+async fn check_challenge(challenge: &TotpChallenge, code: &str) -> Result<Option<(SysUser, Option<Vec<String>>)>> {
+    let user = select_user(challenge.user_id).await?;
+    match &challenge.enroll_secret {
+        Some(secret) => {
+            if !verify_code(challenge.user_id, secret, code).await? {
+                return Ok(None);
+            }
+            let recovery_codes = enable(challenge.user_id, secret).await?;
+            Ok(Some((user, Some(recovery_codes))))
+        }
+        None => Ok(verify_user_code(&user, code).await?.then_some((user, None))),
+    }
+}
+
+/// put the challenge back with the rest of its lifetime
+async fn put_back(key: &str, challenge: &TotpChallenge) -> Result<()> {
+    let ttl = challenge.expire_at.saturating_sub(get_timestamp());
+    if ttl > 0 {
+        CONTEXT.cache_service.set_json(key, challenge, ttl).await?;
+    }
+    Ok(())
+}
+
+/// start enrolment of a logged in user, confirm it with `confirm`
+pub async fn enroll(user_id: u64) -> Result<TotpEnrollData> {
+    let user = select_user(user_id).await?;
+    if has_totp(&user) {
+        return error_info!("totp_enabled");
+    }
+    let data = new_enroll(&user.mobile);
+    CONTEXT
+        .cache_service
+        .set_string(&format!("{}{}", CACHE_KEY_PENDING, user_id), &data.secret, PENDING_TTL)
+        .await?;
+    Ok(data)
+}
+
+/// confirm the enrolment with the first code, return the recovery codes
+pub async fn confirm(user_id: u64, code: &str) -> Result<Vec<String>> {
+    let key = format!("{}{}", CACHE_KEY_PENDING, user_id);
+    let secret = CONTEXT.cache_service.get_string(&key).await?;
+    if secret.is_empty() {
+        return Error::err("请先获取两步验证密钥");
+    }
+    if !verify_code(user_id, &secret, code).await? {
+        return error_info!("verify_code_error");
+    }
+    let recovery_codes = enable(user_id, &secret).await?;
+    CONTEXT.cache_service.remove(&key).await?;
+    Ok(recovery_codes)
+}
+
+/// turn off with a code or a recovery code
+pub async fn disable(user_id: u64, code: &str) -> Result<u64> {
+    let user = select_user(user_id).await?;
+    if !has_totp(&user) {
+        return error_info!("totp_not_enabled");
+    }
+    if !verify_user_code(&user, code).await? {
+        return error_info!("verify_code_error");
+    }
+    let result = SysUser::update_totp(pool!(), user_id, None, None).await?;
+    Ok(result.rows_affected)
+}
+
+/// admin clears the totp of a user who lost the device and the recovery codes
+pub async fn reset(user_id: u64) -> Result<u64> {
+    info!("totp reset: {}", user_id);
+    let result = SysUser::update_totp(pool!(), user_id, None, None).await?;
+    Ok(result.rows_affected)
+}
+
+async fn select_user(user_id: u64) -> Result<SysUser> {
+    match SysUser::select_by_id(pool!(), user_id).await? {
+        Some(user) => Ok(user),
+        None => Error::err("用户不存在"),
+    }
+}
+
+fn new_enroll(account: &str) -> TotpEnrollData {
+    let secret = totp::generate_secret();
+    let otpauth_uri = totp::otpauth_uri(&CONTEXT.config.totp_issuer, account, &secret);
+    TotpEnrollData { secret, otpauth_uri }
+}
+
+/// save the secret and new recovery codes, return the plain recovery codes
+async fn enable(user_id: u64, secret: &str) -> Result<Vec<String>> {
+    let recovery_codes: Vec<String> = (0..RECOVERY_CODE_NUM)
+        .map(|_| {
+            let s = random_string(10).to_lowercase();
+            format!("{}-{}", &s[..5], &s[5..])
+        })
+        .collect();
+    let hashes: Vec<String> = recovery_codes.iter().map(Password::hash).collect();
+    let hashes = serde_json::to_string(&hashes).unwrap_or_default();
+    SysUser::update_totp(pool!(), user_id, Some(secret), Some(hashes.as_str())).await?;
+    Ok(recovery_codes)
+}
+
+/// a code of the current time step, every step can only be used once
+async fn verify_code(user_id: u64, secret: &str, code: &str) -> Result<bool> {
+    let step = match totp::verify(secret, code, get_timestamp()) {
+        Some(step) => step,
+        None => return Ok(false),
+    };
+    // set-if-greater, of parallel requests with the same code only one passes
+    let key = format!("{}{}", CACHE_KEY_LAST_STEP, user_id);
+    CONTEXT.cache_service.set_max(&key, step as i64, 120).await
+}
+
+/// `xxxxx-xxxxx` of lowercase letters and digits, see `enable`
+fn is_recovery_code(code: &str) -> bool {
+    code.len() == 11
+        && code.char_indices().all(|(i, c)| if i == 5 { c == '-' } else { c.is_ascii_lowercase() || c.is_ascii_digit() })
+}
+
+/// a code of the user's secret or one of the recovery codes, a recovery code is removed after use
+async fn verify_user_code(user: &SysUser, code: &str) -> Result<bool> {
+    let user_id = user.id.unwrap_or_default();
+    let secret = user.totp_secret.as_deref().unwrap_or_default();
+    if verify_code(user_id, secret, code).await? {
+        return Ok(true);
+    }
+
+    let code = code.trim().to_lowercase();
+    if !is_recovery_code(&code) {
+        return Ok(false);
+    }
+    // limit the tries before hashing
+    let try_key = format!("{}{}", CACHE_KEY_RECOVERY_TRY, user_id);
+    if CONTEXT.cache_service.incr(&try_key, RECOVERY_TRY_TTL).await? > RECOVERY_MAX_TRY {
+        let ttl = CONTEXT.cache_service.ttl(&try_key).await?;
+        return error_info!("req_frequently", ttl.to_string());
+    }
+    let mut hashes: Vec<String> = user.totp_recovery.as_deref()
+        .and_then(|s| serde_json::from_str(s).ok())
+        .unwrap_or_default();
+    match hashes.iter().position(|h| Password::verify(&code, h)) {
+        Some(i) => {
+            hashes.remove(i);
+            let hashes = serde_json::to_string(&hashes).unwrap_or_default();
+            SysUser::update_totp(pool!(), user_id, Some(secret), Some(hashes.as_str())).await?;
+            info!("user {} used a recovery code", user_id);
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_recovery_code() {
+        assert!(is_recovery_code("ab3de-9xyz0"));
+        assert!(!is_recovery_code("123456"));
+        assert!(!is_recovery_code("ab3de9xyz0"));
+        assert!(!is_recovery_code("ab3de-9xyz0a"));
+        assert!(!is_recovery_code("ab3de_9xyz0"));
+        assert!(!is_recovery_code("AB3DE-9XYZ0"));
+    }
+}
@@ -1,70 +1,233 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use rayon::iter::{IntoParallelIterator, IntoParallelRefIterator, ParallelIterator};
 use rbatis::plugin::page::PageRequest;
 use rbatis::Page;
 use rbatis::rbdc::datetime::DateTime;
 use rbs::to_value;
 use log::info;
-use crate::service::login_service;
-use crate::{pool, Error};
-use crate::middleware::context::UserContext;
+use crate::service::authenticator;
+use crate::service::authenticator::local::local_secret;
+use crate::service::{captcha_service, data_scope_service, login_log_service, login_service, oidc_service, password_service, post_service, session_service, sms_service, token_service, totp_service};
+use crate::{error_info, pool, Error};
+use crate::middleware::context::{ClientInfo, UserContext};
+use crate::model::login_log::*;
 use crate::model::menu::{SysMenu, SysMenuUrl};
+use crate::model::post::SysPost;
 use crate::model::role::SysRole;
 use crate::model::user::SysUser;
+use crate::model::user_post::SysUserPost;
 use crate::model::user_role::SysUserRole;
 use crate::utils::jwt_util::JWTToken;
 use crate::utils::password::Password;
+use crate::vo::oidc_vo::OidcLoginReq;
 use crate::vo::user_vo::*;
 use crate::Result;
 
 
 // 后台用户登录
-pub async fn login(item: UserLoginReq) -> Result<String> {
+pub async fn login(item: UserLoginReq, client: ClientInfo) -> Result<UserLoginData> {
+    let mut user_id = None;
+    let result = login_password(&item, &client, &mut user_id).await;
+    login_log_service::record(LOGIN_TYPE_PASSWORD, &item.mobile, user_id, &client, &result);
+    result
+}
+
+// user_id在查到账号后设置, 登录日志使用
+async fn login_password(item: &UserLoginReq, client: &ClientInfo, user_id: &mut Option<u64>) -> Result<UserLoginData> {
     let try_num = login_service::is_need_wait_login_ex(&item.mobile).await?;
+    if login_service::is_need_captcha(try_num) {
+        if item.captcha_id.is_empty() || item.captcha_code.is_empty() {
+            return error_info!("captcha_required")
+        }
+        captcha_service::verify(&item.captcha_id, &item.captcha_code).await?;
+    }
 
     let rb = pool!();
 
     let user_result = SysUser::select_by_mobile(rb, &item.mobile).await;
     // info!("select_by_mobile: {:?}", user_result);
 
-    let user= match user_result {
-        Ok(Some(user)) => {
-            user
-        }
-        Ok(None) => {
-            return Error::err("用户不存在")
-        }
+    let user = match user_result {
+        Ok(user) => user,
         Err(e) => {
             info!("select_by_mobile err: {:?}", e);
             return Error::err("查询用户异常")
         }
     };
-    if !Password::verify(&item.password, &user.password) {
-        login_service::add_retry_login_limit_num(&item.mobile).await?;
-        
-        return Error::err("密码不正确")
+    *user_id = user.as_ref().and_then(|u| u.id);
+    if let Some(user) = &user {
+        login_service::check_locked(user)?;
+    }
+    // 本地账号校验密码, 目录账号在目录服务校验
+    let Some(authenticator) = authenticator::for_user(user.as_ref()) else {
+        return match user {
+            None => error_info!("account_not_exists"),
+            Some(_) => error_info!("login_use_sso"),
+        }
+    };
+    let user = match authenticator.authenticate(&item.mobile, &item.password, user).await? {
+        Some(user) => user,
+        None => {
+            login_service::add_retry_login_limit_num(&item.mobile).await?;
+            return error_info!("password_error")
+        }
+    };
+    // 目录账号可能刚创建
+    *user_id = user.id;
+    if try_num > 0 || user.locked_until.is_some() {
+        login_service::unlock(&user).await?;
     }
-    if try_num > 0 {
-        login_service::remove_retry_login_limit_num(&item.mobile).await?;
+    if user.is_local() && password_service::is_expired(&user) {
+        return error_info!("password_expired")
     }
+    login_user(user, client).await
+}
+
+// 发送登录验证码
+pub async fn send_code(item: SendCodeReq) -> Result<()> {
+    sms_service::send_code(&item.mobile).await
+}
+
+// 验证码登录
+pub async fn login_code(item: UserLoginCodeReq, client: ClientInfo) -> Result<UserLoginData> {
+    let mut user_id = None;
+    let result = login_by_code(&item, &client, &mut user_id).await;
+    login_log_service::record(LOGIN_TYPE_CODE, &item.mobile, user_id, &client, &result);
+    result
+}
+
+async fn login_by_code(item: &UserLoginCodeReq, client: &ClientInfo, user_id: &mut Option<u64>) -> Result<UserLoginData> {
+    sms_service::verify_code(&item.mobile, &item.code).await?;
+
+    let user = match SysUser::select_by_mobile(pool!(), &item.mobile).await? {
+        Some(user) => user,
+        None => return error_info!("account_not_exists"),
+    };
+    *user_id = user.id;
+    login_service::check_locked(&user)?;
+    login_user(user, client).await
+}
+
+// 单点登录, 身份提供方已验证用户, 不再要求两步验证
+pub async fn login_oidc(item: OidcLoginReq, client: ClientInfo) -> Result<UserLoginData> {
+    let user = match oidc_service::authenticate(&item).await {
+        Ok(claims) => oidc_service::find_or_provision(&claims).await,
+        Err(e) => Err(e),
+    };
+    // 身份提供方验证失败时还不知道是哪个账号
+    let (account, user_id, result) = match user {
+        Ok(user) => (user.mobile.clone(), user.id, login_oidc_user(user, &client).await),
+        Err(e) => (String::new(), None, Err(e)),
+    };
+    login_log_service::record(LOGIN_TYPE_OIDC, &account, user_id, &client, &result);
+    result
+}
+
+async fn login_oidc_user(user: SysUser, client: &ClientInfo) -> Result<UserLoginData> {
+    login_service::check_locked(&user)?;
+    if user.status_id != 1 {
+        return error_info!("account_disabled")
+    }
+
+    let id = user.id.unwrap();
+    create_login_data(id, &user.user_name, None, client).await
+}
+
+// 第一步验证通过, 需要两步验证时返回挑战令牌, 否则签发令牌
+async fn login_user(user: SysUser, client: &ClientInfo) -> Result<UserLoginData> {
     if user.status_id!= 1 {
-        return Error::err("用户已被禁用")
+        return error_info!("account_disabled")
     }
     
+    if totp_service::is_required(&user).await? {
+        let (challenge_token, totp_enroll) = totp_service::create_challenge(&user).await?;
+        return Ok(UserLoginData {
+            challenge_token: Some(challenge_token),
+            totp_enroll,
+            ..Default::default()
+        })
+    }
+
+    let id = user.id.unwrap();
+    create_login_data(id, &user.user_name, None, client).await
+}
+
+// 两步验证登录, 使用第一步返回的挑战令牌和验证码
+pub async fn login_totp(item: LoginTotpReq, client: ClientInfo) -> Result<UserLoginData> {
+    // 挑战令牌无效时还不知道是哪个账号
+    let (account, user_id, result) = match totp_service::verify_challenge(&item).await {
+        Ok((user, recovery_codes)) => (user.mobile.clone(), user.id, login_totp_user(user, recovery_codes, &client).await),
+        Err(e) => (String::new(), None, Err(e)),
+    };
+    login_log_service::record(LOGIN_TYPE_TOTP, &account, user_id, &client, &result);
+    result
+}
+
+async fn login_totp_user(user: SysUser, recovery_codes: Option<Vec<String>>, client: &ClientInfo) -> Result<UserLoginData> {
+    if user.status_id != 1 {
+        return error_info!("account_disabled")
+    }
+
     let id = user.id.unwrap();
-    let username = user.user_name;
+    let mut data = create_login_data(id, &user.user_name, None, client).await?;
+    data.recovery_codes = recovery_codes;
+    Ok(data)
+}
+
+// 使用刷新令牌换取新的令牌, 刷新令牌只能使用一次
+pub async fn refresh_token(item: RefreshTokenReq, client: ClientInfo) -> Result<UserLoginData> {
+    let info = match token_service::take_refresh_token(&item.refresh_token).await? {
+        Some(info) => info,
+        None => return Error::err("刷新令牌无效或已过期"),
+    };
 
+    let rb = pool!();
+    let user = match SysUser::select_by_id(rb, info.user_id).await? {
+        Some(user) => user,
+        None => return Error::err("用户不存在"),
+    };
+    if user.status_id != 1 {
+        return Error::err("用户已被禁用")
+    }
+
+    create_login_data(info.user_id, &user.user_name, Some(info.jti), &client).await
+}
+
+// 后台用户退出登录, 令牌加入吊销列表直到过期
+pub async fn logout(token: JWTToken) -> Result<()> {
+    let exp = session_service::token_exp(&token.jti).await?.max(token.exp());
+    token_service::revoke(&token.jti, exp).await?;
+    session_service::remove(&token.jti).await?;
+    Ok(())
+}
+
+// 签发令牌和刷新令牌, jti为空时开始一个新的会话, 否则延续会话
+async fn create_login_data(id: u64, username: &str, jti: Option<String>, client: &ClientInfo) -> Result<UserLoginData> {
     let btn_menu = query_btn_menu(id).await;
     // info!("btn_menu: {:?}", btn_menu);
     if btn_menu.is_empty() {
         return Error::err("用户没有分配角色或者菜单,不能登录")
     }
 
-    let token = JWTToken::new(id, &username, btn_menu).create_token()?;
-    Ok(token)
+    let mut jwt_token = JWTToken::new(id, username, btn_menu);
+    jwt_token.ver = token_service::perm_version(id).await?;
+    match jti {
+        Some(jti) => {
+            jwt_token.jti = jti;
+            session_service::touch(&jwt_token, client).await?;
+        }
+        None => session_service::create(&jwt_token, client).await?,
+    }
+    let token = jwt_token.create_token()?;
+    let refresh_token = token_service::create_refresh_token(&jwt_token).await?;
+    Ok(UserLoginData {
+        token,
+        refresh_token,
+        ..Default::default()
+    })
 }
 
-async fn query_btn_menu(id: u64) -> Vec<String> {
+pub(crate) async fn query_btn_menu(id: u64) -> Vec<String> {
     let rb = pool!();
     let user_role = SysUserRole::is_admin(rb, id).await;
     if user_role.is_err() {
@@ -116,7 +279,7 @@ pub async fn query_user_role(item: QueryUserRoleReq) -> Result<QueryUserRoleData
 
 }
 
-pub async fn update_user_role(item: UpdateUserRoleReq) -> Result<u64> {
+pub async fn update_user_role(operator_id: u64, item: UpdateUserRoleReq) -> Result<u64> {
     let rb = pool!();
 
     let user_id = item.user_id;
@@ -126,6 +289,7 @@ pub async fn update_user_role(item: UpdateUserRoleReq) -> Result<u64> {
     if user_id == 1 {
         return Error::err("不能修改超级管理员的角色")
     }
+    data_scope_service::check_users(operator_id, &[user_id]).await?;
 
     let _ = SysUserRole::delete_by_column(rb, "user_id", user_id).await?;
 
@@ -145,10 +309,94 @@ pub async fn update_user_role(item: UpdateUserRoleReq) -> Result<u64> {
     }
 
     let result = SysUserRole::insert_batch(rb, &sys_role_user_list, len as u64).await?;
+    token_service::bump_perm_version(&[user_id]).await?;
 
     Ok(result.rows_affected)
 }
 
+// 查询用户的岗位
+pub async fn query_user_post(item: QueryUserPostReq) -> Result<QueryUserPostData> {
+    let rb = pool!();
+
+    let user_post_ids = SysUserPost::select_by_column(rb, "user_id", item.user_id).await?
+        .into_iter().map(|x| x.post_id).collect();
+    let sys_post_list = SysPost::select_all(rb).await?
+        .into_iter().map(|x| x.into()).collect();
+
+    Ok(QueryUserPostData {
+        sys_post_list,
+        user_post_ids,
+    })
+}
+
+// 更新用户的岗位
+pub async fn update_user_post(operator_id: u64, item: UpdateUserPostReq) -> Result<u64> {
+    let rb = pool!();
+    let user_id = item.user_id;
+    data_scope_service::check_users(operator_id, &[user_id]).await?;
+
+    // 重复的岗位会违反唯一索引, 在删除旧数据前去重
+    let mut post_ids = item.post_ids;
+    post_ids.sort_unstable();
+    post_ids.dedup();
+
+    let _ = SysUserPost::delete_by_column(rb, "user_id", user_id).await?;
+    if post_ids.is_empty() {
+        return Ok(0)
+    }
+    let user_posts: Vec<SysUserPost> = post_ids.iter()
+        .map(|post_id| SysUserPost::new(user_id, *post_id))
+        .collect();
+
+    let result = SysUserPost::insert_batch(rb, &user_posts, post_ids.len() as u64).await?;
+    Ok(result.rows_affected)
+}
+
+/// roles of the directory groups from the role mapping, plus the default roles
+pub(crate) fn map_group_roles(groups: &[String], role_mapping: &HashMap<String, Vec<i32>>, default_role_ids: &[i32]) -> Vec<i32> {
+    let mut role_ids = default_role_ids.to_vec();
+    for group in groups {
+        if let Some(ids) = role_mapping.get(group) {
+            role_ids.extend(ids);
+        }
+    }
+    role_ids.sort_unstable();
+    role_ids.dedup();
+    role_ids
+}
+
+// 用户的角色由外部目录管理, 角色不同时替换为映射的角色
+pub(crate) async fn sync_user_roles(user_id: u64, role_ids: &[i32]) -> Result<()> {
+    // 超级管理员只在本地管理
+    if user_id == 1 {
+        return Ok(())
+    }
+    let rb = pool!();
+    let mut current: Vec<i32> = SysUserRole::select_by_column(rb, "user_id", user_id).await?
+        .into_iter().map(|r| r.role_id).collect();
+    current.sort_unstable();
+    if current == role_ids {
+        return Ok(())
+    }
+
+    SysUserRole::delete_by_column(rb, "user_id", user_id).await?;
+    if !role_ids.is_empty() {
+        let time = Some(DateTime::now());
+        let list: Vec<SysUserRole> = role_ids.iter().map(|role_id| SysUserRole {
+            id: None,
+            create_time: time.clone(),
+            update_time: time.clone(),
+            status_id: 1,
+            sort: 1,
+            role_id: *role_id,
+            user_id,
+        }).collect();
+        SysUserRole::insert_batch(rb, &list, list.len() as u64).await?;
+    }
+    token_service::bump_perm_version(&[user_id]).await?;
+    Ok(())
+}
+
 pub async fn query_user_menu(content: UserContext) -> Result<QueryUserMenuData> {
 
     let rb = pool!();
@@ -208,14 +456,21 @@ pub async fn query_user_menu(content: UserContext) -> Result<QueryUserMenuData>
 
 
 // 查询用户列表
-pub async fn user_list(item: UserListReq) -> Result<Page<UserListData>> {
+pub async fn user_list(operator_id: u64, item: UserListReq) -> Result<Page<UserListData>> {
     let rb = pool!();
 
     let mobile = item.mobile.as_deref().unwrap_or_default();
     let status_id = item.status_id.as_deref().unwrap_or_default();
     let page_req = PageRequest::new(item.page_no, item.page_size);
-    let result = SysUser::select_page_by_name(rb, &page_req, mobile, status_id).await?;
-    let page = Page::<UserListData>::from(result);
+    let query = SysUser::select_page_by_name(rb, &page_req, mobile, status_id, item.dept_id);
+    let result = data_scope_service::scoped(operator_id, query).await?;
+    let mut page = Page::<UserListData>::from(result);
+
+    let user_ids: Vec<u64> = page.records.iter().map(|x| x.id).collect();
+    let mut post_names = post_service::post_names_of(&user_ids).await?;
+    for user in &mut page.records {
+        user.post_names = post_names.remove(&user.id).unwrap_or_default();
+    }
     Ok(page)
     
 }
@@ -225,41 +480,61 @@ pub async fn user_list(item: UserListReq) -> Result<Page<UserListData>> {
 pub async fn user_save(item: UserSaveReq) -> Result<u64> {
 
     let rb = pool!();
+    let password = item.password.clone().filter(|p| !p.is_empty());
     let mut sys_user = SysUser::from(item);
-    sys_user.password = Password::md5_and_hash(&sys_user.password);
+    sys_user.password = match password {
+        Some(password) => {
+            password_service::check_policy(&password)?;
+            password_service::hash(&password)
+        }
+        // 默认密码按登录页的摘要保存, 首次登录必须修改
+        None => {
+            sys_user.password_update_time = None;
+            password_service::hash(&sys_user.password)
+        }
+    };
 
     let result = SysUser::insert(rb, &sys_user).await?;
+    if let Some(id) = result.last_insert_id.as_u64() {
+        password_service::add_history(id, &sys_user.password).await?;
+    }
 
     Ok(result.rows_affected)
 }
 
 // 更新用户信息
-pub async fn user_update(item: UserUpdateReq) -> Result<u64> {
+pub async fn user_update(operator_id: u64, item: UserUpdateReq) -> Result<u64> {
 
     let rb = pool!();
+    data_scope_service::check_users(operator_id, &[item.id]).await?;
     let result = SysUser::select_by_id(rb, item.id).await?;
 
     match result {
         None => {
             Error::err("用户不存在")
         }
-        Some(_user) => {
+        Some(user) => {
             let result = UserUpdateReq::update_by_column(rb, &item, "id")
                 .await?;
+            if user.status_id != item.status_id {
+                token_service::bump_perm_version(&[item.id]).await?;
+            }
             Ok(result.rows_affected)
         }
     }
 }
 
 // 删除用户信息
-pub async fn user_delete(item: UserDeleteReq) -> Result<u64> {
+pub async fn user_delete(operator_id: u64, item: UserDeleteReq) -> Result<u64> {
     let rb = pool!();
     //id为1的用户为系统预留用户,不能删除
     let ids: Vec<u64> = item.ids.par_iter()
         .filter(|x| **x != 1).cloned()
         .collect();
+    data_scope_service::check_users(operator_id, &ids).await?;
 
     let result = SysUser::delete_in_column(rb, "id",&ids).await?;
+    token_service::bump_perm_version(&ids).await?;
     Ok(result.rows_affected)
 }
 
@@ -274,19 +549,75 @@ pub async fn update_user_password(item: UpdateUserPwdReq) -> Result<u64> {
             Error::err("用户不存在")
         }
         Some(user) => {
-            if Password::verify(&item.password, &user.password) {
-                let id = user.id.unwrap();
-                let password = Password::hash(&item.new_password);
-                let result = SysUser::update_password(rb, id, &password).await;
-                info!("update_user_pwd result: {:?}", result);
-                if result.is_ok() {
-                    Ok(1)
-                } else {
-                    Error::err("密码修改失败")
-                }
+            if Password::verify(&local_secret(&item.password), &user.password) {
+                change_password(&user, &item.new_password).await
             } else {
                 Error::err("旧密码不正确")
             }
         }
     }
+}
+
+// 密码过期不能登录时, 使用旧密码修改密码
+pub async fn update_expired_password(item: UpdateExpiredPwdReq) -> Result<u64> {
+    login_service::is_need_wait_login_ex(&item.mobile).await?;
+
+    let user = match SysUser::select_by_mobile(pool!(), &item.mobile).await? {
+        Some(user) => user,
+        None => return error_info!("account_not_exists"),
+    };
+    login_service::check_locked(&user)?;
+    if !Password::verify(&local_secret(&item.password), &user.password) {
+        login_service::add_retry_login_limit_num(&item.mobile).await?;
+        return Error::err("旧密码不正确")
+    }
+    change_password(&user, &item.new_password).await
+}
+
+// 查询被锁定的用户
+pub async fn locked_user_list(operator_id: u64, item: LockedUserListReq) -> Result<Page<LockedUserData>> {
+    let page_req = PageRequest::new(item.page_no, item.page_size);
+    let query = SysUser::select_page_locked(pool!(), &page_req, DateTime::now());
+    let result = data_scope_service::scoped(operator_id, query).await?;
+    Ok(Page::<LockedUserData>::from(result))
+}
+
+// 解锁用户
+pub async fn user_unlock(operator_id: u64, item: UserUnlockReq) -> Result<u64> {
+    let rb = pool!();
+    data_scope_service::check_users(operator_id, &item.ids).await?;
+    let users = SysUser::select_in_column(rb, "id", &item.ids).await?;
+    for user in &users {
+        login_service::unlock(user).await?;
+    }
+    Ok(users.len() as u64)
+}
+
+// 忘记密码, 发送重置令牌
+pub async fn forgot_password(item: ForgotPasswordReq) -> Result<()> {
+    password_service::forgot_password(&item.mobile).await
+}
+
+// 使用重置令牌设置新密码, 并强制所有会话下线
+pub async fn reset_password(item: ResetPasswordReq) -> Result<()> {
+    password_service::reset_password(&item.token, &item.new_password).await
+}
+
+// 检查密码策略和历史密码后修改密码
+async fn change_password(user: &SysUser, new_password: &str) -> Result<u64> {
+    if !user.is_local() {
+        return Error::err("外部认证的账号不能修改密码")
+    }
+    let id = user.id.unwrap();
+    password_service::check_policy(new_password)?;
+    password_service::check_history(user, new_password).await?;
+
+    let password = password_service::hash(new_password);
+    let result = password_service::save_password(id, &password).await;
+    info!("update_user_pwd result: {:?}", result);
+    if result.is_ok() {
+        Ok(1)
+    } else {
+        Error::err("密码修改失败")
+    }
 }
\ No newline at end of file
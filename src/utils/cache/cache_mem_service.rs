@@ -1,7 +1,7 @@
 
  use dashmap::DashMap;
  use std::sync::Arc;
- use std::time::{Duration, Instant};
+ use std::time::{Duration, Instant, SystemTime};
  use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 
  use crate::error::Result;
@@ -121,6 +121,93 @@
          let r = self.cache.remove(k).is_some();
          Ok(r)
      }
+
+     async fn take_string(&self, k: &str) -> Result<String> {
+         let v = match self.cache.remove(k) {
+             Some((_, (v, t))) if t.is_none_or(|i| i > Instant::now()) => v,
+             _ => String::new(),
+         };
+         Ok(v)
+     }
+
+     async fn set_add(&self, k: &str, member: &str, ex: u64) -> Result<()> {
+         let now = Instant::now();
+         // the set is a json array, the entry lock makes read-modify-write atomic
+         let mut v = self.cache.entry(k.to_string()).or_insert_with(|| (String::new(), None));
+         let expired = v.1.is_some_and(|i| i <= now);
+         let mut members: Vec<String> = if expired { Vec::new() } else { serde_json::from_str(&v.0).unwrap_or_default() };
+         if !members.iter().any(|m| m == member) {
+             members.push(member.to_string());
+         }
+         v.0 = serde_json::to_string(&members).unwrap_or_default();
+         v.1 = if ex == 0 { None } else { now.checked_add(Duration::from_secs(ex)) };
+         Ok(())
+     }
+
+     async fn set_remove(&self, k: &str, member: &str) -> Result<()> {
+         if let Some(mut v) = self.cache.get_mut(k) {
+             let mut members: Vec<String> = serde_json::from_str(&v.0).unwrap_or_default();
+             members.retain(|m| m != member);
+             v.0 = serde_json::to_string(&members).unwrap_or_default();
+         }
+         Ok(())
+     }
+
+     async fn set_members(&self, k: &str) -> Result<Vec<String>> {
+         let members = match self.cache.get(k) {
+             Some(v) if v.1.is_none_or(|i| i > Instant::now()) => serde_json::from_str(&v.0).unwrap_or_default(),
+             _ => Vec::new(),
+         };
+         Ok(members)
+     }
+
+     async fn incr(&self, k: &str, ex: u64) -> Result<i64> {
+         let now = Instant::now();
+         // the entry holds the shard lock, so get-then-set is atomic
+         let mut v = self.cache.entry(k.to_string()).or_insert_with(|| (String::new(), None));
+         let expired = v.1.is_some_and(|i| i <= now);
+         let num = if expired { 0 } else { v.0.parse::<i64>().unwrap_or(0) } + 1;
+         if num == 1 || v.1.is_none() {
+             v.1 = if ex == 0 { None } else { now.checked_add(Duration::from_secs(ex)) };
+         }
+         v.0 = num.to_string();
+         Ok(num)
+     }
+
+     async fn set_max(&self, k: &str, v: i64, ex: u64) -> Result<bool> {
+         let now = Instant::now();
+         // the entry lock makes compare-and-set atomic
+         let mut e = self.cache.entry(k.to_string()).or_insert_with(|| (String::new(), None));
+         let expired = e.1.is_some_and(|i| i <= now);
+         if !expired && e.0.parse::<i64>().is_ok_and(|current| current >= v) {
+             return Ok(false);
+         }
+         e.0 = v.to_string();
+         e.1 = if ex == 0 { None } else { now.checked_add(Duration::from_secs(ex)) };
+         Ok(true)
+     }
+
+     async fn take_token(&self, k: &str, capacity: u64, rate: f64) -> Result<u64> {
+         let now_ms = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default().as_millis() as f64;
+         let capacity = capacity as f64;
+         // the value is "tokens last_refill_ms", the entry lock makes refill-and-take atomic
+         let mut v = self.cache.entry(k.to_string()).or_insert_with(|| (String::new(), None));
+         let mut parts = v.0.split(' ').map(|s| s.parse::<f64>().ok());
+         let (tokens, ts) = match (parts.next().flatten(), parts.next().flatten()) {
+             (Some(tokens), Some(ts)) => (tokens, ts),
+             _ => (capacity, now_ms),
+         };
+         let mut tokens = (tokens + (now_ms - ts).max(0.0) / 1000.0 * rate).min(capacity);
+         let wait = if tokens >= 1.0 {
+             tokens -= 1.0;
+             0
+         } else {
+             ((1.0 - tokens) / rate).ceil() as u64
+         };
+         v.0 = format!("{} {}", tokens, now_ms);
+         v.1 = Instant::now().checked_add(Duration::from_secs_f64(capacity / rate + 1.0));
+         Ok(wait)
+     }
  }
  
  #[cfg(test)]
@@ -148,6 +235,97 @@
      }
  
 
+     #[tokio::test]
+     async fn test_mem_set() {
+         let m = MemCacheService::new();
+         m.set_add("s", "a", 0).await.unwrap();
+         m.set_add("s", "b", 0).await.unwrap();
+         m.set_add("s", "a", 0).await.unwrap();
+         assert_eq!(m.set_members("s").await.unwrap(), vec!["a", "b"]);
+         m.set_remove("s", "a").await.unwrap();
+         assert_eq!(m.set_members("s").await.unwrap(), vec!["b"]);
+         assert!(m.set_members("none").await.unwrap().is_empty());
+     }
+
+     #[tokio::test]
+     async fn test_mem_incr_ttl() {
+         let m = MemCacheService::new();
+         assert_eq!(m.incr("n", 100).await.unwrap(), 1);
+         assert_eq!(m.incr("n", 100).await.unwrap(), 2);
+         let ttl = m.ttl("n").await.unwrap();
+         assert!(ttl > 0 && ttl <= 100);
+
+         // a counter left without an expiry gets one
+         m.set_string("n", "5", 0).await.unwrap();
+         assert_eq!(m.incr("n", 100).await.unwrap(), 6);
+         assert!(m.ttl("n").await.unwrap() > 0);
+
+         // the count starts over once expired
+         m.cache.insert("n".to_string(), ("6".to_string(), Some(Instant::now())));
+         assert_eq!(m.incr("n", 100).await.unwrap(), 1);
+     }
+
+     #[tokio::test]
+     async fn test_mem_set_max() {
+         let m = MemCacheService::new();
+         assert!(m.set_max("max", 5, 100).await.unwrap());
+         assert!(!m.set_max("max", 5, 100).await.unwrap());
+         assert!(!m.set_max("max", 4, 100).await.unwrap());
+         assert!(m.set_max("max", 6, 100).await.unwrap());
+         assert_eq!(m.get_string("max").await.unwrap(), "6");
+
+         // an expired value no longer counts
+         m.cache.insert("max".to_string(), ("9".to_string(), Some(Instant::now())));
+         assert!(m.set_max("max", 7, 100).await.unwrap());
+     }
+
+     /// the bucket of `k` with `tokens` left, last refilled `ago_ms` before now
+     fn bucket(m: &MemCacheService, k: &str, tokens: f64, ago_ms: u64) {
+         let now_ms = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap().as_millis() as u64;
+         m.cache.insert(k.to_string(), (format!("{} {}", tokens, now_ms - ago_ms), None));
+     }
+
+     #[tokio::test]
+     async fn test_mem_take_token_capacity() {
+         let m = MemCacheService::new();
+         for _ in 0..3 {
+             assert_eq!(m.take_token("tb", 3, 0.5).await.unwrap(), 0);
+         }
+         // empty, one token comes back after 1 / 0.5 seconds
+         assert_eq!(m.take_token("tb", 3, 0.5).await.unwrap(), 2);
+         assert_eq!(m.take_token("tb", 3, 0.5).await.unwrap(), 2);
+         assert!(m.ttl("tb").await.unwrap() > 0);
+     }
+
+     #[tokio::test]
+     async fn test_mem_take_token_refill() {
+         let m = MemCacheService::new();
+         // 2 seconds at 1 token per second
+         bucket(&m, "tb", 0.0, 2000);
+         assert_eq!(m.take_token("tb", 5, 1.0).await.unwrap(), 0);
+         assert_eq!(m.take_token("tb", 5, 1.0).await.unwrap(), 0);
+         assert_eq!(m.take_token("tb", 5, 1.0).await.unwrap(), 1);
+
+         // the refill stops at the capacity
+         bucket(&m, "tb", 0.0, 60_000);
+         for _ in 0..5 {
+             assert_eq!(m.take_token("tb", 5, 1.0).await.unwrap(), 0);
+         }
+         assert_eq!(m.take_token("tb", 5, 1.0).await.unwrap(), 1);
+     }
+
+     #[tokio::test]
+     async fn test_mem_take_token_retry_after() {
+         let m = MemCacheService::new();
+         // a quarter token missing at 0.1 tokens per second is 2.5 seconds, rounded up
+         bucket(&m, "tb", 0.75, 0);
+         assert_eq!(m.take_token("tb", 10, 0.1).await.unwrap(), 3);
+         // a rejected request doesn't take the partial token
+         bucket(&m, "tb", 0.5, 0);
+         assert_eq!(m.take_token("tb", 10, 0.1).await.unwrap(), 5);
+         assert_eq!(m.take_token("tb", 10, 0.1).await.unwrap(), 5);
+     }
+
      #[tokio::test]
      async fn test_mem_ttl() {
          let m = MemCacheService::new();
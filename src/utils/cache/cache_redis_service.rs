@@ -1,9 +1,56 @@
 use crate::error::{Error, Result};
 use crate::redis_conn;
-use bb8_redis::redis::AsyncCommands;
+use bb8_redis::redis::{self, AsyncCommands, Script};
+use std::sync::OnceLock;
 
 use super::cache_service::ICacheService;
 
+/// token bucket in a hash of tokens and last refill time, the script runs atomically on the server
+const TAKE_TOKEN_SCRIPT: &str = r"
+local capacity = tonumber(ARGV[1])
+local rate = tonumber(ARGV[2])
+local t = redis.call('TIME')
+local now = tonumber(t[1]) * 1000 + math.floor(tonumber(t[2]) / 1000)
+local bucket = redis.call('HMGET', KEYS[1], 'tokens', 'ts')
+local tokens = tonumber(bucket[1]) or capacity
+local ts = tonumber(bucket[2]) or now
+tokens = math.min(capacity, tokens + math.max(0, now - ts) / 1000 * rate)
+local wait = 0
+if tokens >= 1 then
+    tokens = tokens - 1
+else
+    wait = math.ceil((1 - tokens) / rate)
+end
+redis.call('HSET', KEYS[1], 'tokens', tostring(tokens), 'ts', tostring(now))
+redis.call('EXPIRE', KEYS[1], math.ceil(capacity / rate) + 1)
+return wait
+";
+
+/// counter with the expiry set in the same step, a key left without one (e.g. written by an older
+/// version that sent EXPIRE separately) gets it on the next increase
+const INCR_SCRIPT: &str = r"
+local num = redis.call('INCR', KEYS[1])
+local ex = tonumber(ARGV[1])
+if ex > 0 and (num == 1 or redis.call('TTL', KEYS[1]) == -1) then
+    redis.call('EXPIRE', KEYS[1], ex)
+end
+return num
+";
+
+/// compare-and-set of an integer, only a greater value replaces the current one
+const SET_MAX_SCRIPT: &str = r"
+local current = tonumber(redis.call('GET', KEYS[1]))
+if current and current >= tonumber(ARGV[1]) then
+    return 0
+end
+if tonumber(ARGV[2]) > 0 then
+    redis.call('SET', KEYS[1], ARGV[1], 'EX', ARGV[2])
+else
+    redis.call('SET', KEYS[1], ARGV[1])
+end
+return 1
+";
+
 /// Redis Cache service
 #[derive(Debug)]
 pub struct RedisCacheService {}
@@ -23,11 +70,12 @@ impl ICacheService for RedisCacheService {
     async fn get_string(&self, k: &str) -> Result<String> {
         let mut conn = redis_conn!().unwrap();
         //  let result: RedisResult<Option<String>> = redis::cmd("GET").arg(&[k]).query_async(&mut conn).await;
-        let result: String = conn
+        // a missing key is nil, return empty string like MemCacheService
+        let result: Option<String> = conn
             .get(k)
             .await
             .map_err(|e| Error::Internal(format!("RedisService get_string({}) fail: {}", k, e)))?;
-        Ok(result)
+        Ok(result.unwrap_or_default())
     }
 
     ///set_string Automatically expire
@@ -63,6 +111,81 @@ impl ICacheService for RedisCacheService {
         conn.del(k).await.map_err(|e| Error::Internal(format!("RedisService del fail: {}", e)))
            
     }
+
+    async fn take_string(&self, k: &str) -> Result<String> {
+        let mut conn = redis_conn!().unwrap();
+        let result: Option<String> = conn
+            .get_del(k)
+            .await
+            .map_err(|e| Error::Internal(format!("RedisService take_string({}) fail: {}", k, e)))?;
+        Ok(result.unwrap_or_default())
+    }
+
+    async fn set_add(&self, k: &str, member: &str, ex: u64) -> Result<()> {
+        let mut conn = redis_conn!().unwrap();
+        let mut pipe = redis::pipe();
+        pipe.atomic().sadd(k, member).ignore();
+        if ex > 0 {
+            pipe.expire(k, ex as i64).ignore();
+        }
+        pipe.query_async(&mut *conn)
+            .await
+            .map_err(|e| Error::Internal(format!("RedisService set_add fail: {}", e)))
+    }
+
+    async fn set_remove(&self, k: &str, member: &str) -> Result<()> {
+        let mut conn = redis_conn!().unwrap();
+        conn.srem(k, member)
+            .await
+            .map_err(|e| Error::Internal(format!("RedisService set_remove fail: {}", e)))
+            .map(|_: i64| ())
+    }
+
+    async fn set_members(&self, k: &str) -> Result<Vec<String>> {
+        let mut conn = redis_conn!().unwrap();
+        conn.smembers(k)
+            .await
+            .map_err(|e| Error::Internal(format!("RedisService set_members fail: {}", e)))
+    }
+
+    async fn incr(&self, k: &str, ex: u64) -> Result<i64> {
+        static SCRIPT: OnceLock<Script> = OnceLock::new();
+        let mut conn = redis_conn!().unwrap();
+        SCRIPT
+            .get_or_init(|| Script::new(INCR_SCRIPT))
+            .key(k)
+            .arg(ex)
+            .invoke_async(&mut *conn)
+            .await
+            .map_err(|e| Error::Internal(format!("RedisService incr fail: {}", e)))
+    }
+
+    async fn set_max(&self, k: &str, v: i64, ex: u64) -> Result<bool> {
+        static SCRIPT: OnceLock<Script> = OnceLock::new();
+        let mut conn = redis_conn!().unwrap();
+        SCRIPT
+            .get_or_init(|| Script::new(SET_MAX_SCRIPT))
+            .key(k)
+            .arg(v)
+            .arg(ex)
+            .invoke_async(&mut *conn)
+            .await
+            .map(|set: i64| set == 1)
+            .map_err(|e| Error::Internal(format!("RedisService set_max fail: {}", e)))
+    }
+
+    async fn take_token(&self, k: &str, capacity: u64, rate: f64) -> Result<u64> {
+        static SCRIPT: OnceLock<Script> = OnceLock::new();
+        let mut conn = redis_conn!().unwrap();
+        SCRIPT
+            .get_or_init(|| Script::new(TAKE_TOKEN_SCRIPT))
+            .key(k)
+            .arg(capacity)
+            .arg(rate)
+            .invoke_async(&mut *conn)
+            .await
+            .map_err(|e| Error::Internal(format!("RedisService take_token fail: {}", e)))
+    }
 }
 
 #[cfg(test)]
@@ -89,4 +212,21 @@ mod tests {
         }
         println!("time get: {:?}", now.elapsed());
     }
+
+    #[ignore]
+    #[tokio::test]
+    async fn test_redis_incr_ttl() {
+        let rs = RedisCacheService::new();
+        rs.remove("test_incr").await.unwrap();
+        assert_eq!(rs.incr("test_incr", 100).await.unwrap(), 1);
+        assert_eq!(rs.incr("test_incr", 100).await.unwrap(), 2);
+        let ttl = rs.ttl("test_incr").await.unwrap();
+        assert!(ttl > 0 && ttl <= 100);
+
+        // a counter left without an expiry gets one
+        rs.set_string("test_incr", "5", 0).await.unwrap();
+        assert_eq!(rs.incr("test_incr", 100).await.unwrap(), 6);
+        assert!(rs.ttl("test_incr").await.unwrap() > 0);
+        rs.remove("test_incr").await.unwrap();
+    }
 }
@@ -20,6 +20,31 @@ pub trait ICacheService: Sync + Send + Debug {
 
     async fn remove(&self, k: &str) -> Result<bool>;
 
+    /// atomic get and remove the value of key, empty string when it does not exist.
+    /// of concurrent callers only one gets the value
+    async fn take_string(&self, k: &str) -> Result<String>;
+
+    /// atomic add member to the set of key, the set expires ex seconds later, 0 = no expire
+    async fn set_add(&self, k: &str, member: &str, ex: u64) -> Result<()>;
+
+    /// atomic remove member from the set of key
+    async fn set_remove(&self, k: &str, member: &str) -> Result<()>;
+
+    /// members of the set of key, empty when it does not exist
+    async fn set_members(&self, k: &str) -> Result<Vec<String>>;
+
+    /// atomic increase the integer value of key by 1 and return it, ex seconds expire set in the same step
+    /// when the key is created or has no expire, 0 = no expire
+    async fn incr(&self, k: &str, ex: u64) -> Result<i64>;
+
+    /// atomic set the integer value of key to v when v is greater than the current value, ex seconds expire, 0 = no expire.
+    /// return whether it was set, of concurrent callers with the same v only one sets it
+    async fn set_max(&self, k: &str, v: i64, ex: u64) -> Result<bool>;
+
+    /// token bucket, take one token from the bucket holding up to `capacity` tokens refilled by `rate` tokens per second.
+    /// return 0 when a token is taken, otherwise the seconds until one is available
+    async fn take_token(&self, k: &str, capacity: u64, rate: f64) -> Result<u64>;
+
 }
 
 
@@ -122,5 +147,10 @@ async fn test_cache_service() {
     let _ = cache.set_json("test_json", &123, 0).await;
     let v = cache.get_json::<i32>("test_json").await.unwrap();
     assert_eq!(v, 123);
+    assert_eq!(cache.incr("test_incr", 0).await.unwrap(), 1);
+    assert_eq!(cache.incr("test_incr", 0).await.unwrap(), 2);
+    assert_eq!(cache.take_token("test_bucket", 2, 0.5).await.unwrap(), 0);
+    assert_eq!(cache.take_token("test_bucket", 2, 0.5).await.unwrap(), 0);
+    assert_eq!(cache.take_token("test_bucket", 2, 0.5).await.unwrap(), 2);
     
 }
\ No newline at end of file
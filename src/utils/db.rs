@@ -1,7 +1,7 @@
 use std::{sync::Arc, time::Duration};
 use rbatis::RBatis;
 use log::info;
-use crate::{config::Config, service::sys_trash_service::SysTrashService};
+use crate::{config::Config, service::{data_scope_service::DataScopeIntercept, sys_trash_service::SysTrashService}};
 
 /// init database pool
 pub async fn init_db(config: &Config, rb: &RBatis){
@@ -11,6 +11,7 @@ pub async fn init_db(config: &Config, rb: &RBatis){
         .await
         .expect("[abs_admin] rbatis pool init fail!");
     rb.intercepts.push(Arc::new(SysTrashService::new()));
+    rb.intercepts.push(Arc::new(DataScopeIntercept));
     let pool = rb.get_pool().unwrap();
     //max connections
     pool.set_max_open_conns(config.db.max_connections as u64).await;
@@ -1,19 +1,38 @@
 use std::time::SystemTime;
+use rand::distributions::Alphanumeric;
+use rand::Rng;
 
 pub mod jwt_util;
-<<<<<<< HEAD
-pub mod redis_util;
-pub mod password;
-=======
 pub mod redis;
 pub mod password;
 pub mod cache;
 pub mod db;
 mod macros;
->>>>>>> dev
+pub mod permission;
+pub mod totp;
+pub mod sms;
+pub mod notifier;
+pub mod redact;
 
 pub fn get_timestamp() -> u64 {
     let now = SystemTime::now();
     now.duration_since(SystemTime::UNIX_EPOCH).unwrap().as_secs()
 
-}
\ No newline at end of file
+}
+
+/// random alphanumeric string, use for opaque tokens
+pub fn random_string(len: usize) -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(len)
+        .map(char::from)
+        .collect()
+}
+
+/// percent-encode a query parameter value
+pub fn url_encode(s: &str) -> String {
+    s.bytes().map(|b| match b {
+        b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+        _ => format!("%{:02X}", b),
+    }).collect()
+}
@@ -0,0 +1,35 @@
+use std::sync::OnceLock;
+
+use log::info;
+
+use crate::error::Result;
+
+/// delivers messages to users, implement it for mail or sms
+#[async_trait]
+pub trait Notifier: Sync + Send {
+    /// `to` is the mobile of the user
+    async fn notify(&self, to: &str, subject: &str, content: &str) -> Result<()>;
+}
+
+/// development notifier, writes the message to the log
+pub struct LogNotifier;
+
+#[async_trait]
+impl Notifier for LogNotifier {
+    async fn notify(&self, to: &str, subject: &str, content: &str) -> Result<()> {
+        info!("notify {} [{}]: {}", to, subject, content);
+        Ok(())
+    }
+}
+
+static NOTIFIER: OnceLock<Box<dyn Notifier>> = OnceLock::new();
+
+/// the installed notifier, `LogNotifier` by default
+pub fn notifier() -> &'static dyn Notifier {
+    NOTIFIER.get_or_init(|| Box::new(LogNotifier)).as_ref()
+}
+
+/// install another notifier before the first message is sent, returns false if one is already in use
+pub fn set_notifier(notifier: Box<dyn Notifier>) -> bool {
+    NOTIFIER.set(notifier).is_ok()
+}
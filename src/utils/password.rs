@@ -1,9 +1,25 @@
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::{Algorithm, Argon2, Params, Version};
+use rand::rngs::OsRng;
+
+/// hashes are stored in the PHC string format which records the algorithm,
+/// `$argon2id$v=19$...` for new hashes. `$2b$...` bcrypt and bare hex md5
+/// hashes are legacy and only verified, they are upgraded at login.
+/// A bare md5 row holds the digest the login page sends, it is compared as is
 pub struct Password {}
 
 impl Password {
-    /// Hash a password using bcrypt
+    fn argon2() -> Argon2<'static> {
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, Params::default())
+    }
+
+    /// Hash a password using argon2id
     pub fn hash(password: impl AsRef<[u8]>) -> String {
-        bcrypt::hash(password.as_ref(), bcrypt::DEFAULT_COST).unwrap_or_default()
+        let salt = SaltString::generate(&mut OsRng);
+        Password::argon2()
+            .hash_password(password.as_ref(), &salt)
+            .map(|h| h.to_string())
+            .unwrap_or_default()
     }
 
     pub fn md5(password: impl AsRef<[u8]>) -> String {
@@ -11,20 +27,56 @@ impl Password {
         format!("{:x}", digest)
     }
 
-    /// Hash a password using md5 and then hash using bcrypt
+    /// Hash a password using md5 and then hash using argon2id,
+    /// the client sends the md5 of the password at login
     pub fn md5_and_hash(password: impl AsRef<[u8]>) -> String {
         let md5_password = Password::md5(password);
         Password::hash(md5_password)
     }
 
     pub fn verify(raw_password: &str, hash: &str) -> bool {
-        if raw_password.eq(hash) {
+        if hash.starts_with("$argon2") {
+            return PasswordHash::new(hash)
+                .and_then(|h| Password::argon2().verify_password(raw_password.as_bytes(), &h))
+                .is_ok();
+        }
+        if hash.starts_with("$2") {
+            return bcrypt::verify(raw_password, hash).unwrap_or(false);
+        }
+        if Password::is_md5(hash) {
+            return constant_eq(raw_password.to_ascii_lowercase().as_bytes(), hash.to_ascii_lowercase().as_bytes());
+        }
+        false
+    }
+
+    /// 32 hex characters, a md5 digest
+    pub fn is_md5(s: &str) -> bool {
+        s.len() == 32 && s.bytes().all(|b| b.is_ascii_hexdigit())
+    }
+
+    /// the hash is not argon2id with the current parameters, hash the password again after it verified
+    pub fn needs_rehash(hash: &str) -> bool {
+        let Ok(h) = PasswordHash::new(hash) else {
             return true;
+        };
+        if h.algorithm != Algorithm::Argon2id.ident() {
+            return true;
+        }
+        match Params::try_from(&h) {
+            Ok(params) => {
+                let current = Params::default();
+                params.m_cost() != current.m_cost() || params.t_cost() != current.t_cost() || params.p_cost() != current.p_cost()
+            }
+            Err(_) => true,
         }
-        bcrypt::verify(raw_password, hash).unwrap_or(false)
     }
 }
 
+
+fn constant_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -38,7 +90,7 @@ mod test {
         
         let s = Password::md5_and_hash("123456");
         println!("{}", s);
-        
+        assert!(s.starts_with("$argon2id$"));
     }
 
     #[test]
@@ -46,14 +98,33 @@ mod test {
         let password = "123456";
         let raw_password = "123456";
 
-        assert!(Password::verify(password, raw_password));
+        // plaintext is not a hash
+        assert!(!Password::verify(password, raw_password));
 
         let hash = Password::hash(password);
         assert!(Password::verify(password, &hash));
+        assert!(!Password::verify("654321", &hash));
+        assert!(!Password::needs_rehash(&hash));
         let hash = Password::md5_and_hash(password);
         println!("{}", hash);
         let md5= Password::md5(password);
         println!("{}", md5);
         assert!(Password::verify(&md5, &hash));
     }
+
+    #[test]
+    fn test_verify_legacy() {
+        let md5 = Password::md5("123456");
+        let bcrypt = bcrypt::hash(&md5, 4).unwrap();
+        assert!(Password::verify(&md5, &bcrypt));
+        assert!(Password::needs_rehash(&bcrypt));
+
+        // the row holds the digest the login page sends
+        assert!(Password::verify(&md5, &md5));
+        assert!(Password::verify(&md5.to_uppercase(), &md5));
+        assert!(!Password::verify("123456", &md5));
+        assert!(!Password::verify(&Password::md5(&md5), &md5));
+        assert!(!Password::verify(&Password::md5("654321"), &md5));
+        assert!(Password::needs_rehash(&md5));
+    }
 }
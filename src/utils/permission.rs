@@ -0,0 +1,234 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use axum::http::Method;
+use log::warn;
+use parking_lot::RwLock;
+
+/// Matches a request against `sys_menu.api_url` or `white_list_api` entries. An entry is a path
+/// with an optional method prefix, a path segment can be a `:param`, a `prefix*` or a trailing `*`:
+/// `/api/user_list`, `POST /api/user/:id`, `/api/login*`, `/api/report/*`
+#[derive(Debug, Default)]
+pub struct PermissionMatcher {
+    /// plain paths, any method
+    exact: HashSet<String>,
+    /// plain paths with methods
+    exact_method: HashMap<String, Vec<Method>>,
+    patterns: Vec<Pattern>,
+}
+
+#[derive(Debug)]
+struct Pattern {
+    method: Option<Method>,
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug, PartialEq)]
+enum Segment {
+    Static(String),
+    /// `:name`, any single non-empty segment
+    Param,
+    /// `name*`, a single segment starting with name
+    Prefix(String),
+    /// `*`, one or more remaining segments
+    Wildcard,
+}
+
+impl PermissionMatcher {
+    pub fn new<S: AsRef<str>>(permissions: &[S]) -> Self {
+        let mut matcher = Self::default();
+        for permission in permissions {
+            matcher.add(permission.as_ref());
+        }
+        matcher
+    }
+
+    fn add(&mut self, permission: &str) {
+        let permission = permission.trim();
+        let (method, path) = match permission.split_once(char::is_whitespace) {
+            Some((method, path)) => match Method::from_bytes(method.to_uppercase().as_bytes()) {
+                Ok(method) => (Some(method), path.trim()),
+                Err(_) => {
+                    warn!("invalid permission method: {}", permission);
+                    return;
+                }
+            },
+            None => (None, permission),
+        };
+        // the query string never takes part in matching
+        let path = path.split('?').next().unwrap_or_default();
+        if path.is_empty() {
+            return;
+        }
+
+        let segments: Vec<Segment> = path.split('/').map(|s| match s {
+            "*" => Segment::Wildcard,
+            s if s.starts_with(':') => Segment::Param,
+            s if s.ends_with('*') => Segment::Prefix(s.trim_end_matches('*').to_string()),
+            s => Segment::Static(s.to_string()),
+        }).collect();
+
+        if segments.iter().all(|s| matches!(s, Segment::Static(_))) {
+            match method {
+                None => {
+                    self.exact.insert(path.to_string());
+                }
+                Some(method) => self.exact_method.entry(path.to_string()).or_default().push(method),
+            }
+            return;
+        }
+        if segments.iter().rev().skip(1).any(|s| *s == Segment::Wildcard) {
+            warn!("permission wildcard must be the last segment: {}", permission);
+            return;
+        }
+        self.patterns.push(Pattern { method, segments });
+    }
+
+    /// `path` must not contain the query string, use `uri.path()`
+    pub fn is_match(&self, method: &Method, path: &str) -> bool {
+        if self.exact.contains(path) {
+            return true;
+        }
+        if let Some(methods) = self.exact_method.get(path) {
+            if methods.contains(method) {
+                return true;
+            }
+        }
+        if self.patterns.is_empty() {
+            return false;
+        }
+        let segments: Vec<&str> = path.split('/').collect();
+        self.patterns.iter().any(|p| p.is_match(method, &segments))
+    }
+}
+
+impl Pattern {
+    fn is_match(&self, method: &Method, path: &[&str]) -> bool {
+        if self.method.as_ref().is_some_and(|m| m != method) {
+            return false;
+        }
+        for (i, segment) in self.segments.iter().enumerate() {
+            match segment {
+                Segment::Wildcard => return path.len() > i,
+                Segment::Param => match path.get(i) {
+                    Some(s) if !s.is_empty() => {}
+                    _ => return false,
+                },
+                Segment::Prefix(prefix) => match path.get(i) {
+                    Some(s) if s.starts_with(prefix.as_str()) => {}
+                    _ => return false,
+                },
+                Segment::Static(s) => {
+                    if path.get(i) != Some(&s.as_str()) {
+                        return false;
+                    }
+                }
+            }
+        }
+        path.len() == self.segments.len()
+    }
+}
+
+/// compiled matchers by owner (user or api key), an entry is rebuilt when the owner's
+/// permission version changes or the permissions differ from the ones it was built from,
+/// the version alone is not enough as the counter starts over when the cache is flushed
+#[derive(Debug, Default)]
+pub struct MatcherCache {
+    entries: RwLock<HashMap<u64, MatcherEntry>>,
+}
+
+#[derive(Debug)]
+struct MatcherEntry {
+    version: i64,
+    permissions: Vec<String>,
+    matcher: Arc<PermissionMatcher>,
+}
+
+impl MatcherEntry {
+    fn is_valid<S: AsRef<str>>(&self, version: i64, permissions: &[S]) -> bool {
+        self.version == version
+            && self.permissions.len() == permissions.len()
+            && self.permissions.iter().zip(permissions).all(|(a, b)| a == b.as_ref())
+    }
+}
+
+impl MatcherCache {
+    pub fn get<S: AsRef<str>>(&self, owner: u64, version: i64, permissions: &[S]) -> Arc<PermissionMatcher> {
+        if let Some(entry) = self.entries.read().get(&owner) {
+            if entry.is_valid(version, permissions) {
+                return entry.matcher.clone();
+            }
+        }
+        let matcher = Arc::new(PermissionMatcher::new(permissions));
+        self.entries.write().insert(owner, MatcherEntry {
+            version,
+            permissions: permissions.iter().map(|p| p.as_ref().to_string()).collect(),
+            matcher: matcher.clone(),
+        });
+        matcher
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_permission_match() {
+        let matcher = PermissionMatcher::new(&[
+            "/api/user_list",
+            "POST /api/user/:id",
+            "get /api/role_list?x=1",
+            "/api/report/*",
+            "/api/login*",
+            "/api/*/x",
+        ]);
+        assert!(matcher.is_match(&Method::POST, "/api/user_list"));
+        assert!(matcher.is_match(&Method::GET, "/api/user_list"));
+        assert!(!matcher.is_match(&Method::GET, "/api/user_list/1"));
+
+        assert!(matcher.is_match(&Method::POST, "/api/user/12"));
+        assert!(!matcher.is_match(&Method::GET, "/api/user/12"));
+        assert!(!matcher.is_match(&Method::POST, "/api/user/"));
+        assert!(!matcher.is_match(&Method::POST, "/api/user/12/role"));
+
+        assert!(matcher.is_match(&Method::GET, "/api/role_list"));
+        assert!(!matcher.is_match(&Method::POST, "/api/role_list"));
+
+        assert!(matcher.is_match(&Method::GET, "/api/report/day"));
+        assert!(matcher.is_match(&Method::GET, "/api/report/day/2024"));
+        assert!(!matcher.is_match(&Method::GET, "/api/report"));
+
+        assert!(matcher.is_match(&Method::POST, "/api/login"));
+        assert!(matcher.is_match(&Method::POST, "/api/login_code"));
+        assert!(!matcher.is_match(&Method::POST, "/api/login/x"));
+
+        // wildcard in the middle is ignored
+        assert!(!matcher.is_match(&Method::GET, "/api/a/x"));
+    }
+
+    #[test]
+    fn test_matcher_cache() {
+        let cache = MatcherCache::default();
+        let m1 = cache.get(1, 0, &["/api/user_list"]);
+        // same version and permissions, the compiled matcher is reused
+        let m2 = cache.get(1, 0, &["/api/user_list"]);
+        assert!(Arc::ptr_eq(&m1, &m2));
+
+        let m3 = cache.get(1, 1, &["/api/role_list"]);
+        assert!(!m3.is_match(&Method::POST, "/api/user_list"));
+        assert!(m3.is_match(&Method::POST, "/api/role_list"));
+        assert!(!Arc::ptr_eq(&cache.get(2, 1, &["/api/role_list"]), &m3));
+    }
+
+    #[test]
+    fn test_matcher_cache_version_reset() {
+        let cache = MatcherCache::default();
+        let m1 = cache.get(1, 0, &["/api/user_list"]);
+        // the version counter started over after a cache flush, the permissions decide
+        let m2 = cache.get(1, 0, &["/api/role_list"]);
+        assert!(!Arc::ptr_eq(&m1, &m2));
+        assert!(!m2.is_match(&Method::POST, "/api/user_list"));
+        assert!(m2.is_match(&Method::POST, "/api/role_list"));
+    }
+}
@@ -0,0 +1,77 @@
+use serde_json::Value;
+
+pub const REDACTED: &str = "******";
+
+/// The request body for the operation log. Values of `fields` in a json body are replaced by
+/// `REDACTED` at any depth, field names compare case-insensitively. The result is cut to `max_len` bytes
+pub fn redact_body(body: &[u8], fields: &[String], max_len: usize) -> String {
+    let text = match serde_json::from_slice::<Value>(body) {
+        Ok(mut value) => {
+            redact_value(&mut value, fields);
+            value.to_string()
+        }
+        // not json, the whole body is hidden when it may hold a field
+        Err(_) => {
+            let text = String::from_utf8_lossy(body).into_owned();
+            let lower = text.to_lowercase();
+            if fields.iter().any(|f| lower.contains(&f.to_lowercase())) {
+                REDACTED.to_string()
+            } else {
+                text
+            }
+        }
+    };
+    truncate(text, max_len)
+}
+
+fn redact_value(value: &mut Value, fields: &[String]) {
+    match value {
+        Value::Object(map) => {
+            for (key, v) in map.iter_mut() {
+                if fields.iter().any(|f| f.eq_ignore_ascii_case(key)) {
+                    *v = Value::String(REDACTED.to_string());
+                } else {
+                    redact_value(v, fields);
+                }
+            }
+        }
+        Value::Array(arr) => arr.iter_mut().for_each(|v| redact_value(v, fields)),
+        _ => {}
+    }
+}
+
+fn truncate(mut text: String, max_len: usize) -> String {
+    if text.len() > max_len {
+        let mut end = max_len;
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        text.truncate(end);
+    }
+    text
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_redact_body() {
+        let fields = vec!["password".to_string(), "new_password".to_string()];
+        let body = br#"{"mobile":"18500000000","Password":"e10adc","users":[{"new_password":"x","id":1}]}"#;
+        let s = redact_body(body, &fields, 1024);
+        assert!(!s.contains("e10adc"));
+        assert!(!s.contains(r#""x""#));
+        assert!(s.contains("18500000000"));
+        assert_eq!(s.matches(REDACTED).count(), 2);
+
+        assert_eq!(redact_body(b"a=1&password=2", &fields, 1024), REDACTED);
+        assert_eq!(redact_body(b"a=1&b=2", &fields, 1024), "a=1&b=2");
+    }
+
+    #[test]
+    fn test_truncate() {
+        assert_eq!(redact_body("中文字符".as_bytes(), &[], 4), "中");
+        assert_eq!(redact_body(b"abc", &[], 0), "");
+    }
+}
@@ -36,7 +36,7 @@ async fn test_redis_pool() {
 
     let now = std::time::Instant::now();
     for i in 0..100 {
-        let _:bool = conn.set_ex(&format!("key{}", i), "value", 100).await.unwrap();
+        let _:bool = conn.set_ex(format!("key{}", i), "value", 100).await.unwrap();
     //  let _: String = redis::cmd("SETEX").arg(&format!("key{}", i)).arg(100).arg("value").query_async(&mut *conn).await.unwrap();
 
     };
@@ -0,0 +1,60 @@
+use std::sync::OnceLock;
+
+use log::info;
+use tokio::io::AsyncWriteExt;
+
+use crate::error::Result;
+use crate::service::CONTEXT;
+use crate::utils::get_timestamp;
+
+/// sends verification codes, implement it for a real sms provider
+#[async_trait]
+pub trait SmsSender: Sync + Send {
+    async fn send_code(&self, mobile: &str, code: &str) -> Result<()>;
+}
+
+/// development sender, writes the code to the log
+pub struct LogSmsSender;
+
+#[async_trait]
+impl SmsSender for LogSmsSender {
+    async fn send_code(&self, mobile: &str, code: &str) -> Result<()> {
+        info!("sms code to {}: {}", mobile, code);
+        Ok(())
+    }
+}
+
+/// development sender, appends `timestamp mobile code` lines to a file
+pub struct FileSmsSender {
+    pub path: String,
+}
+
+#[async_trait]
+impl SmsSender for FileSmsSender {
+    async fn send_code(&self, mobile: &str, code: &str) -> Result<()> {
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await?;
+        file.write_all(format!("{} {} {}\n", get_timestamp(), mobile, code).as_bytes()).await?;
+        Ok(())
+    }
+}
+
+static SMS_SENDER: OnceLock<Box<dyn SmsSender>> = OnceLock::new();
+
+/// the sender chosen by `sms_sender`, "log" or "file"
+pub fn sms_sender() -> &'static dyn SmsSender {
+    SMS_SENDER
+        .get_or_init(|| match CONTEXT.config.sms_sender.as_str() {
+            "file" => Box::new(FileSmsSender { path: CONTEXT.config.sms_file.clone() }),
+            _ => Box::new(LogSmsSender),
+        })
+        .as_ref()
+}
+
+/// install another sender before the first code is sent, returns false if one is already in use
+pub fn set_sms_sender(sender: Box<dyn SmsSender>) -> bool {
+    SMS_SENDER.set(sender).is_ok()
+}
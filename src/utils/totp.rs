@@ -0,0 +1,63 @@
+use data_encoding::BASE32_NOPAD;
+use hmac::{Hmac, Mac};
+use sha1::Sha1;
+
+use super::url_encode;
+
+/// RFC 6238 defaults, supported by all authenticator apps
+const DIGITS: u32 = 6;
+const PERIOD: u64 = 30;
+/// accept codes of one step before and after for clock drift
+const SKEW: u64 = 1;
+
+/// new random base32 secret (160 bits)
+pub fn generate_secret() -> String {
+    let bytes: [u8; 20] = rand::random();
+    BASE32_NOPAD.encode(&bytes)
+}
+
+/// otpauth uri for the authenticator app qrcode
+pub fn otpauth_uri(issuer: &str, account: &str, secret: &str) -> String {
+    format!(
+        "otpauth://totp/{}:{}?secret={}&issuer={}&algorithm=SHA1&digits={}&period={}",
+        url_encode(issuer), url_encode(account), secret, url_encode(issuer), DIGITS, PERIOD
+    )
+}
+
+/// verify the code at timestamp, return the matched time step
+pub fn verify(secret: &str, code: &str, timestamp: u64) -> Option<u64> {
+    let key = BASE32_NOPAD.decode(secret.trim_end_matches('=').as_bytes()).ok()?;
+    let code: u32 = code.trim().parse().ok()?;
+    let step = timestamp / PERIOD;
+    (step.saturating_sub(SKEW)..=step + SKEW).find(|s| hotp(&key, *s) == code)
+}
+
+/// RFC 4226 HOTP
+fn hotp(key: &[u8], counter: u64) -> u32 {
+    let mut mac = Hmac::<Sha1>::new_from_slice(key).expect("hmac accepts any key length");
+    mac.update(&counter.to_be_bytes());
+    let hash = mac.finalize().into_bytes();
+    let offset = (hash[hash.len() - 1] & 0x0f) as usize;
+    let binary = u32::from_be_bytes([hash[offset], hash[offset + 1], hash[offset + 2], hash[offset + 3]]) & 0x7fff_ffff;
+    binary % 10u32.pow(DIGITS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_totp() {
+        // RFC 6238 appendix B, sha1 secret "12345678901234567890", last 6 digits
+        let secret = BASE32_NOPAD.encode(b"12345678901234567890");
+        assert_eq!(verify(&secret, "287082", 59), Some(1));
+        assert_eq!(verify(&secret, "081804", 1111111109), Some(1111111109 / 30));
+        assert_eq!(verify(&secret, "287083", 59), None);
+        // previous step is still accepted
+        assert_eq!(verify(&secret, "287082", 89), Some(1));
+        assert_eq!(verify(&secret, "287082", 120), None);
+
+        let uri = otpauth_uri("axum admin", "18500000000", &secret);
+        assert!(uri.starts_with("otpauth://totp/axum%20admin:18500000000?secret="));
+    }
+}
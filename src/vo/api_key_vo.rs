@@ -0,0 +1,50 @@
+use rbatis::rbdc::DateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::model::api_key::SysApiKey;
+
+#[derive(Debug, Deserialize)]
+pub struct ApiKeySaveReq {
+    pub key_name: String,
+    /// api_url the key may call, must be owned by the user
+    pub permissions: Vec<String>,
+    /// days until the key expires, 1 to 3650, never expires when empty
+    pub expire_days: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ApiKeySaveData {
+    pub id: u64,
+    /// only returned once, it is stored hashed
+    pub key: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ApiKeyDeleteReq {
+    pub ids: Vec<u64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ApiKeyListData {
+    pub id: u64,
+    pub key_name: String,
+    pub key_prefix: String,
+    pub permissions: Vec<String>,
+    pub expire_time: Option<DateTime>,
+    pub last_used_time: Option<DateTime>,
+    pub create_time: Option<DateTime>,
+}
+
+impl From<SysApiKey> for ApiKeyListData {
+    fn from(k: SysApiKey) -> Self {
+        Self {
+            id: k.id.unwrap_or_default(),
+            key_name: k.key_name,
+            key_prefix: k.key_prefix,
+            permissions: serde_json::from_str(&k.permissions).unwrap_or_default(),
+            expire_time: k.expire_time,
+            last_used_time: k.last_used_time,
+            create_time: k.create_time,
+        }
+    }
+}
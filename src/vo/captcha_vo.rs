@@ -0,0 +1,9 @@
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct CaptchaData {
+    /// send back with the answer as `captcha_id`
+    pub captcha_id: String,
+    /// data url of the png image
+    pub image: String,
+}
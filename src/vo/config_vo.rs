@@ -0,0 +1,82 @@
+use rbatis::rbdc::DateTime;
+use serde::{Deserialize, Serialize};
+use crate::model::sys_config::SysConfig;
+
+#[derive(Debug, Deserialize)]
+pub struct ConfigListReq {
+    #[serde(rename = "current")]
+    pub page_no: u64,
+    #[serde(rename = "pageSize")]
+    pub page_size: u64,
+    pub config_name: Option<String>,
+    pub config_key: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ConfigListData {
+    pub id: i32,
+    pub config_name: String,
+    pub config_key: String,
+    pub config_value: String,
+    pub value_type: String,
+    pub remark: String,
+    pub create_time: String,
+    pub update_time: String,
+}
+
+impl From<SysConfig> for ConfigListData {
+    fn from(config: SysConfig) -> Self {
+        Self {
+            id: config.id.unwrap(),
+            config_name: config.config_name,
+            config_key: config.config_key,
+            config_value: config.config_value,
+            value_type: config.value_type,
+            remark: config.remark.unwrap_or_default(),
+            create_time: config.create_time.unwrap().to_string(),
+            update_time: config.update_time.unwrap().to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ConfigSaveReq {
+    pub config_name: String,
+    pub config_key: String,
+    pub config_value: String,
+    pub value_type: String,
+    pub remark: Option<String>,
+}
+
+impl From<ConfigSaveReq> for SysConfig {
+    fn from(req: ConfigSaveReq) -> Self {
+        let now = Some(DateTime::now());
+        SysConfig {
+            id: None,
+            config_name: req.config_name,
+            config_key: req.config_key,
+            config_value: req.config_value,
+            value_type: req.value_type,
+            remark: req.remark,
+            create_time: now.clone(),
+            update_time: now,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ConfigUpdateReq {
+    pub id: i32,
+    pub config_name: String,
+    pub config_key: String,
+    pub config_value: String,
+    pub value_type: String,
+    pub remark: Option<String>,
+}
+
+impl_update!(ConfigUpdateReq{}, "sys_config");
+
+#[derive(Debug, Deserialize)]
+pub struct ConfigDeleteReq {
+    pub ids: Vec<i32>,
+}
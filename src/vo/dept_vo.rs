@@ -0,0 +1,107 @@
+use rbatis::rbdc::DateTime;
+use serde::{Deserialize, Serialize};
+use crate::model::dept::SysDept;
+
+#[derive(Debug, Deserialize)]
+pub struct DeptListReq {
+    pub dept_name: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DeptListData {
+    pub id: i32,
+    pub sort: i32,
+    pub status_id: i32,
+    pub parent_id: i32,
+    pub ancestors: String,
+    pub dept_name: String,
+    pub leader: String,
+    pub phone: String,
+    pub remark: String,
+    pub create_time: String,
+    pub update_time: String,
+}
+
+impl From<SysDept> for DeptListData {
+    fn from(dept: SysDept) -> Self {
+        Self {
+            id: dept.id.unwrap(),
+            sort: dept.sort,
+            status_id: dept.status_id,
+            parent_id: dept.parent_id,
+            ancestors: dept.ancestors,
+            dept_name: dept.dept_name,
+            leader: dept.leader.unwrap_or_default(),
+            phone: dept.phone.unwrap_or_default(),
+            remark: dept.remark.unwrap_or_default(),
+            create_time: dept.create_time.unwrap().to_string(),
+            update_time: dept.update_time.unwrap().to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeptSaveReq {
+    pub sort: i32,
+    pub status_id: i32,
+    pub parent_id: Option<i32>,
+    pub dept_name: String,
+    pub leader: Option<String>,
+    pub phone: Option<String>,
+    pub remark: Option<String>,
+}
+
+impl From<DeptSaveReq> for SysDept {
+    fn from(req: DeptSaveReq) -> Self {
+        let now = Some(DateTime::now());
+        Self {
+            id: None,
+            sort: req.sort,
+            status_id: req.status_id,
+            parent_id: req.parent_id.unwrap_or_default(),
+            ancestors: String::new(),
+            dept_name: req.dept_name,
+            leader: req.leader,
+            phone: req.phone,
+            remark: req.remark,
+            create_time: now.clone(),
+            update_time: now,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeptUpdateReq {
+    pub id: i32,
+    pub sort: i32,
+    pub status_id: i32,
+    pub parent_id: i32,
+    pub dept_name: String,
+    pub leader: Option<String>,
+    pub phone: Option<String>,
+    pub remark: Option<String>,
+}
+
+impl From<DeptUpdateReq> for SysDept {
+    fn from(req: DeptUpdateReq) -> Self {
+        let now = Some(DateTime::now());
+        Self {
+            id: Some(req.id),
+            sort: req.sort,
+            status_id: req.status_id,
+            parent_id: req.parent_id,
+            ancestors: String::new(),
+            dept_name: req.dept_name,
+            leader: req.leader,
+            phone: req.phone,
+            remark: req.remark,
+            create_time: None,
+            update_time: now,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeptDeleteReq {
+    pub ids: Vec<i32>,
+}
\ No newline at end of file
@@ -0,0 +1,194 @@
+use rbatis::rbdc::DateTime;
+use serde::{Deserialize, Serialize};
+use crate::model::dict_data::SysDictData;
+use crate::model::dict_type::SysDictType;
+
+#[derive(Debug, Deserialize)]
+pub struct DictTypeListReq {
+    #[serde(rename = "current")]
+    pub page_no: u64,
+    #[serde(rename = "pageSize")]
+    pub page_size: u64,
+    pub dict_name: Option<String>,
+    pub dict_type: Option<String>,
+    pub status_id: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DictTypeListData {
+    pub id: i32,
+    pub status_id: i32,
+    pub dict_name: String,
+    pub dict_type: String,
+    pub remark: String,
+    pub create_time: String,
+    pub update_time: String,
+}
+
+impl From<SysDictType> for DictTypeListData {
+    fn from(dict: SysDictType) -> Self {
+        Self {
+            id: dict.id.unwrap(),
+            status_id: dict.status_id,
+            dict_name: dict.dict_name,
+            dict_type: dict.dict_type,
+            remark: dict.remark.unwrap_or_default(),
+            create_time: dict.create_time.unwrap().to_string(),
+            update_time: dict.update_time.unwrap().to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DictTypeSaveReq {
+    pub dict_name: String,
+    pub dict_type: String,
+    pub status_id: i32,
+    pub remark: Option<String>,
+}
+
+impl From<DictTypeSaveReq> for SysDictType {
+    fn from(req: DictTypeSaveReq) -> Self {
+        let now = Some(DateTime::now());
+        SysDictType {
+            id: None,
+            status_id: req.status_id,
+            dict_name: req.dict_name,
+            dict_type: req.dict_type,
+            remark: req.remark,
+            create_time: now.clone(),
+            update_time: now,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct DictTypeUpdateReq {
+    pub id: i32,
+    pub status_id: i32,
+    pub dict_name: String,
+    pub dict_type: String,
+    pub remark: Option<String>,
+}
+
+impl_update!(DictTypeUpdateReq{}, "sys_dict_type");
+
+#[derive(Debug, Deserialize)]
+pub struct DictTypeDeleteReq {
+    pub ids: Vec<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DictDataListReq {
+    #[serde(rename = "current")]
+    pub page_no: u64,
+    #[serde(rename = "pageSize")]
+    pub page_size: u64,
+    pub dict_type: String,
+    pub dict_label: Option<String>,
+    pub status_id: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DictDataListData {
+    pub id: i32,
+    pub sort: i32,
+    pub status_id: i32,
+    pub dict_type: String,
+    pub dict_label: String,
+    pub dict_value: String,
+    pub list_class: String,
+    pub remark: String,
+    pub create_time: String,
+    pub update_time: String,
+}
+
+impl From<SysDictData> for DictDataListData {
+    fn from(data: SysDictData) -> Self {
+        Self {
+            id: data.id.unwrap(),
+            sort: data.sort,
+            status_id: data.status_id,
+            dict_type: data.dict_type,
+            dict_label: data.dict_label,
+            dict_value: data.dict_value,
+            list_class: data.list_class.unwrap_or_default(),
+            remark: data.remark.unwrap_or_default(),
+            create_time: data.create_time.unwrap().to_string(),
+            update_time: data.update_time.unwrap().to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DictDataSaveReq {
+    pub dict_type: String,
+    pub dict_label: String,
+    pub dict_value: String,
+    pub list_class: Option<String>,
+    pub sort: i32,
+    pub status_id: i32,
+    pub remark: Option<String>,
+}
+
+impl From<DictDataSaveReq> for SysDictData {
+    fn from(req: DictDataSaveReq) -> Self {
+        let now = Some(DateTime::now());
+        SysDictData {
+            id: None,
+            sort: req.sort,
+            status_id: req.status_id,
+            dict_type: req.dict_type,
+            dict_label: req.dict_label,
+            dict_value: req.dict_value,
+            list_class: req.list_class,
+            remark: req.remark,
+            create_time: now.clone(),
+            update_time: now,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct DictDataUpdateReq {
+    pub id: i32,
+    /// moves the item to another dict type, unchanged when missing
+    pub dict_type: Option<String>,
+    pub sort: i32,
+    pub status_id: i32,
+    pub dict_label: String,
+    pub dict_value: String,
+    pub list_class: Option<String>,
+    pub remark: Option<String>,
+}
+
+impl_update!(DictDataUpdateReq{}, "sys_dict_data");
+
+#[derive(Debug, Deserialize)]
+pub struct DictDataDeleteReq {
+    pub ids: Vec<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DictLookupReq {
+    /// at most 20, every type must exist
+    pub dict_types: Vec<String>,
+}
+
+/// label of a dict value, cached per dict type
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DictItem {
+    pub label: String,
+    pub value: String,
+    pub list_class: String,
+}
+
+impl From<SysDictData> for DictItem {
+    fn from(data: SysDictData) -> Self {
+        Self {
+            label: data.dict_label,
+            value: data.dict_value,
+            list_class: data.list_class.unwrap_or_default(),
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+use crate::model::login_log::SysLoginLog;
+
+#[derive(Debug, Deserialize)]
+pub struct LoginLogListReq {
+    #[serde(rename = "current")]
+    pub page_no: u64,
+    #[serde(rename = "pageSize")]
+    pub page_size: u64,
+    pub account: Option<String>,
+    /// 0: fail, 1: success, 2: waiting for the totp code
+    pub status: Option<i32>,
+    /// "YYYY-MM-DD hh:mm:ss"
+    pub begin_time: Option<String>,
+    pub end_time: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LoginLogListData {
+    pub id: u64,
+    pub user_id: Option<u64>,
+    pub account: String,
+    pub login_type: String,
+    pub status: i32,
+    pub reason: String,
+    pub msg: String,
+    pub ip: String,
+    pub user_agent: String,
+    pub create_time: String,
+}
+
+impl From<SysLoginLog> for LoginLogListData {
+    fn from(log: SysLoginLog) -> Self {
+        Self {
+            id: log.id.unwrap_or_default(),
+            user_id: log.user_id,
+            account: log.account,
+            login_type: log.login_type,
+            status: log.status,
+            reason: log.reason,
+            msg: log.msg,
+            ip: log.ip,
+            user_agent: log.user_agent,
+            create_time: log.create_time.map(|t| t.to_string()).unwrap_or_default(),
+        }
+    }
+}
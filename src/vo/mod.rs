@@ -7,6 +7,16 @@ use crate::error::Error;
 pub mod menu_vo;
 pub mod role_vo;
 pub mod user_vo;
+pub mod session_vo;
+pub mod api_key_vo;
+pub mod captcha_vo;
+pub mod oidc_vo;
+pub mod dept_vo;
+pub mod post_vo;
+pub mod dict_vo;
+pub mod config_vo;
+pub mod oper_log_vo;
+pub mod login_log_vo;
 
 /// 统一返回vo
 #[derive(Serialize, Debug, Clone)]
@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize)]
+pub struct OidcAuthorizeData {
+    /// redirect the browser here
+    pub authorize_url: String,
+    pub state: String,
+}
+
+/// the redirect_uri page posts the query parameters it received
+#[derive(Debug, Deserialize)]
+pub struct OidcLoginReq {
+    pub code: String,
+    pub state: String,
+}
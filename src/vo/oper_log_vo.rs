@@ -0,0 +1,56 @@
+use serde::{Deserialize, Serialize};
+use crate::model::oper_log::SysOperLog;
+
+#[derive(Debug, Default, Deserialize)]
+pub struct OperLogFilter {
+    pub user_id: Option<u64>,
+    /// path prefix
+    pub path: Option<String>,
+    pub code: Option<i32>,
+    /// "YYYY-MM-DD hh:mm:ss"
+    pub begin_time: Option<String>,
+    pub end_time: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OperLogListReq {
+    #[serde(rename = "current")]
+    pub page_no: u64,
+    #[serde(rename = "pageSize")]
+    pub page_size: u64,
+    #[serde(flatten)]
+    pub filter: OperLogFilter,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OperLogListData {
+    pub id: u64,
+    pub user_id: Option<u64>,
+    pub method: String,
+    pub path: String,
+    pub req_body: String,
+    pub status: i32,
+    pub code: Option<i32>,
+    pub ip: String,
+    pub user_agent: String,
+    pub latency_ms: u64,
+    pub create_time: String,
+}
+
+impl From<SysOperLog> for OperLogListData {
+    fn from(log: SysOperLog) -> Self {
+        Self {
+            id: log.id.unwrap_or_default(),
+            user_id: log.user_id,
+            method: log.method,
+            path: log.path,
+            req_body: log.req_body,
+            status: log.status,
+            code: log.code,
+            ip: log.ip,
+            user_agent: log.user_agent,
+            latency_ms: log.latency_ms,
+            create_time: log.create_time.map(|t| t.to_string()).unwrap_or_default(),
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use rbatis::rbdc::DateTime;
+use serde::{Deserialize, Serialize};
+use crate::model::post::SysPost;
+
+#[derive(Debug, Deserialize)]
+pub struct PostListReq {
+    #[serde(rename = "current")]
+    pub page_no: u64,
+    #[serde(rename = "pageSize")]
+    pub page_size: u64,
+    pub post_name: Option<String>,
+    pub status_id: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PostListData {
+    pub id: i32,
+    pub sort: i32,
+    pub status_id: i32,
+    pub post_code: String,
+    pub post_name: String,
+    pub remark: String,
+    pub create_time: String,
+    pub update_time: String,
+}
+
+impl From<SysPost> for PostListData {
+    fn from(post: SysPost) -> Self {
+        Self {
+            id: post.id.unwrap(),
+            sort: post.sort,
+            status_id: post.status_id,
+            post_code: post.post_code,
+            post_name: post.post_name,
+            remark: post.remark.unwrap_or_default(),
+            create_time: post.create_time.unwrap().to_string(),
+            update_time: post.update_time.unwrap().to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PostSaveReq {
+    pub post_code: String,
+    pub post_name: String,
+    pub sort: i32,
+    pub status_id: i32,
+    pub remark: Option<String>,
+}
+
+impl From<PostSaveReq> for SysPost {
+    fn from(post_req: PostSaveReq) -> Self {
+        let now = Some(DateTime::now());
+        SysPost {
+            id: None,
+            sort: post_req.sort,
+            status_id: post_req.status_id,
+            post_code: post_req.post_code,
+            post_name: post_req.post_name,
+            remark: post_req.remark,
+            create_time: now.clone(),
+            update_time: now,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct PostUpdateReq {
+    pub id: i32,
+    pub sort: i32,
+    pub status_id: i32,
+    pub post_code: String,
+    pub post_name: String,
+    pub remark: Option<String>,
+    /// set by the service on every update
+    #[serde(skip_deserializing)]
+    pub update_time: Option<DateTime>,
+}
+
+impl_update!(PostUpdateReq{}, "sys_post");
+
+#[derive(Debug, Deserialize)]
+pub struct PostDeleteReq {
+    pub ids: Vec<i32>,
+}
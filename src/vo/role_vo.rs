@@ -1,7 +1,7 @@
 use rbatis::rbdc::DateTime;
 use serde::{Deserialize, Serialize};
 use crate::model::menu::SysMenu;
-use crate::model::role::SysRole;
+use crate::model::role::{SysRole, DATA_SCOPE_ALL};
 
 #[derive(Debug, Deserialize)]
 pub struct RoleListReq {
@@ -20,6 +20,8 @@ pub struct RoleListData {
     pub status_id: i32,
     pub role_name: String,
     pub remark: String,
+    pub require_2fa: i32,
+    pub data_scope: i32,
     pub create_time: String,
     pub update_time: String,
 }
@@ -32,6 +34,8 @@ impl From<SysRole> for RoleListData {
             status_id: role.status_id,
             role_name: role.role_name,
             remark: role.remark.unwrap_or_default(),
+            require_2fa: role.require_2fa,
+            data_scope: role.data_scope,
             create_time: role.create_time.unwrap().to_string(),
             update_time: role.update_time.unwrap().to_string(),
         }
@@ -44,6 +48,8 @@ pub struct RoleSaveReq {
     pub sort: i32,
     pub status_id: i32,
     pub remark: Option<String>,
+    pub require_2fa: Option<i32>,
+    pub data_scope: Option<i32>,
 }
 
 impl From<RoleSaveReq> for SysRole {
@@ -55,6 +61,8 @@ impl From<RoleSaveReq> for SysRole {
             status_id: role_req.status_id,
             role_name: role_req.role_name,
             remark: role_req.remark,
+            require_2fa: role_req.require_2fa.unwrap_or_default(),
+            data_scope: role_req.data_scope.unwrap_or(DATA_SCOPE_ALL),
             create_time: now.clone(),
             update_time: now,
         }
@@ -68,6 +76,10 @@ pub struct RoleUpdateReq {
     pub status_id: i32,
     pub role_name: String,
     pub remark: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub require_2fa: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data_scope: Option<i32>,
 }
 
 impl_update!(RoleUpdateReq{}, "sys_role");
@@ -81,6 +93,8 @@ impl From<RoleUpdateReq> for SysRole {
             status_id: role_req.status_id,
             role_name: role_req.role_name,
             remark: role_req.remark,
+            require_2fa: role_req.require_2fa.unwrap_or_default(),
+            data_scope: role_req.data_scope.unwrap_or(DATA_SCOPE_ALL),
             create_time: None,
             update_time: now,
         }
@@ -133,4 +147,18 @@ pub struct UpdateRoleMenuReq {
     pub role_id: i32,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct QueryRoleDeptReq {
+    pub role_id: i32,
+}
 
+#[derive(Debug, Serialize)]
+pub struct QueryRoleDeptData {
+    pub dept_ids: Vec<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateRoleDeptReq {
+    pub role_id: i32,
+    pub dept_ids: Vec<i32>,
+}
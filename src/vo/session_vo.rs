@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+
+/// a login session, the session id is the jti of its tokens
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionInfo {
+    pub session_id: String,
+    pub user_id: u64,
+    pub user_name: String,
+    pub ip: String,
+    pub user_agent: String,
+    pub device: String,
+    /// timestamp of the login
+    pub login_time: u64,
+    /// timestamp of the last token refresh
+    pub active_time: u64,
+    /// expiry of the newest token of the session
+    #[serde(default)]
+    pub token_exp: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OnlineUserData {
+    pub user_id: u64,
+    pub user_name: String,
+    pub session_count: usize,
+    pub ip: String,
+    pub active_time: u64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SessionListReq {
+    pub user_id: u64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SessionKickReq {
+    pub session_ids: Vec<String>,
+}
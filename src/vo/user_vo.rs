@@ -4,11 +4,72 @@ use crate::model::menu::SysMenu;
 
 use crate::model::role::SysRole;
 use crate::model::user::SysUser;
+use crate::vo::post_vo::PostListData;
 
 #[derive(Debug, Deserialize)]
 pub struct UserLoginReq {
     pub mobile: String,
     pub password: String,
+    /// required after `login_captcha_retry` failed logins, from /api/captcha
+    #[serde(default)]
+    pub captcha_id: String,
+    #[serde(default)]
+    pub captcha_code: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SendCodeReq {
+    pub mobile: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UserLoginCodeReq {
+    pub mobile: String,
+    pub code: String,
+}
+
+#[derive(Debug, Serialize, Default)]
+pub struct UserLoginData {
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub token: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub refresh_token: String,
+    /// the second factor is required, send it with the code to /api/login_totp
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub challenge_token: Option<String>,
+    /// the role requires 2fa but the user has not enrolled, add it to the authenticator app first
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub totp_enroll: Option<TotpEnrollData>,
+    /// only returned once, when enrolled at login
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recovery_codes: Option<Vec<String>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TotpEnrollData {
+    pub secret: String,
+    pub otpauth_uri: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LoginTotpReq {
+    pub challenge_token: String,
+    pub code: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TotpCodeReq {
+    pub code: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TotpResetReq {
+    pub user_id: u64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RefreshTokenReq {
+    pub refresh_token: String,
 }
 
 #[derive(Debug, Deserialize)]
@@ -54,6 +115,23 @@ pub struct UpdateUserRoleReq {
     pub role_ids: Vec<i32>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct QueryUserPostReq {
+    pub user_id: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct QueryUserPostData {
+    pub sys_post_list: Vec<PostListData>,
+    pub user_post_ids: Vec<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateUserPostReq {
+    pub user_id: u64,
+    pub post_ids: Vec<i32>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct QueryUserMenuReq {
     pub token: String,
@@ -107,6 +185,8 @@ pub struct UserListReq {
     pub page_size: u64,
     pub mobile: Option<String>,
     pub status_id: Option<String>,
+    /// users of the department and its sub-departments
+    pub dept_id: Option<i32>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -126,10 +206,49 @@ pub struct UserListData {
     pub mobile: String,
     pub user_name: String,
     pub remark: String,
+    pub dept_id: Option<i32>,
+    /// names of the user's positions
+    pub post_names: Vec<String>,
+    pub last_login_time: String,
+    pub last_login_ip: String,
     pub create_time: String,
     pub update_time: String,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct LockedUserListReq {
+    #[serde(rename = "current")]
+    pub page_no: u64,
+    #[serde(rename = "pageSize")]
+    pub page_size: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LockedUserData {
+    pub id: u64,
+    pub mobile: String,
+    pub user_name: String,
+    pub locked_until: String,
+    pub lock_reason: String,
+}
+
+impl From<SysUser> for LockedUserData {
+    fn from(user: SysUser) -> Self {
+        Self {
+            id: user.id.unwrap(),
+            mobile: user.mobile,
+            user_name: user.user_name,
+            locked_until: user.locked_until.map(|t| t.to_string()).unwrap_or_default(),
+            lock_reason: user.lock_reason.unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UserUnlockReq {
+    pub ids: Vec<u64>,
+}
+
 impl From<SysUser> for UserListData {
     fn from(user: SysUser) -> Self {
         Self {
@@ -139,6 +258,10 @@ impl From<SysUser> for UserListData {
             mobile: user.mobile,
             user_name: user.user_name,
             remark: user.remark.unwrap_or_default(),
+            dept_id: user.dept_id,
+            post_names: Vec::new(),
+            last_login_time: user.last_login_time.map(|t| t.to_string()).unwrap_or_default(),
+            last_login_ip: user.last_login_ip.unwrap_or_default(),
             create_time: user.create_time.unwrap().to_string(),
             update_time: user.update_time.unwrap().to_string(),
         }
@@ -151,6 +274,10 @@ pub struct UserSaveReq {
     pub status_id: i32,
     pub sort: i32,
     pub remark: Option<String>,
+    pub dept_id: Option<i32>,
+    /// the plain password, checked against the password policy. when empty the default password
+    /// is used and must be changed at the first login
+    pub password: Option<String>,
 }
 
 impl From<UserSaveReq> for SysUser {
@@ -159,13 +286,22 @@ impl From<UserSaveReq> for SysUser {
         Self {
             id: None,
             create_time: now.clone(),
-            update_time: now,
+            update_time: now.clone(),
             status_id: item.status_id,
             sort: item.sort,
             mobile: item.mobile,
             user_name: item.user_name,
             remark: item.remark,
-            password: "123456".to_string(),//默认密码为123456,暂时不加密
+            dept_id: item.dept_id,
+            password: "123456".to_string(),//默认密码为123456,保存时加密
+            password_update_time: now,
+            auth_source: None,
+            locked_until: None,
+            lock_reason: None,
+            totp_secret: None,
+            totp_recovery: None,
+            last_login_time: None,
+            last_login_ip: None,
         }
     }
 }
@@ -179,6 +315,7 @@ pub struct UserUpdateReq {
     // #[serde(rename(deserialize = "real_name"))]
     pub user_name: String,
     pub remark: Option<String>,
+    pub dept_id: Option<i32>,
 }
 
 impl_update!(UserUpdateReq{}, "sys_user");
@@ -193,4 +330,22 @@ pub struct UpdateUserPwdReq {
     pub id: u64,
     pub password: String,
     pub new_password: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ForgotPasswordReq {
+    pub mobile: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ResetPasswordReq {
+    pub token: String,
+    pub new_password: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateExpiredPwdReq {
+    pub mobile: String,
+    pub password: String,
+    pub new_password: String,
 }
\ No newline at end of file